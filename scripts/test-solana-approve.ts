@@ -100,10 +100,9 @@ async function main() {
     const tokenPrice8d = new anchor.BN(356709); // Current ELIZAOS price
     const solPrice8d = new anchor.BN(20000000000); // $200 in 8 decimals
     const now = Math.floor(Date.now() / 1000);
-    const maxAge = 3600; // 1 hour
 
     await program.methods
-      .setPrices(tokenPrice8d, solPrice8d, new anchor.BN(now), new anchor.BN(maxAge))
+      .setPrices(tokenPrice8d, solPrice8d, new anchor.BN(now))
       .accounts({
         owner: wallet.publicKey,
         desk: DESK,
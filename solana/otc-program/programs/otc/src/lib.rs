@@ -7,10 +7,124 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
 };
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 declare_id!("3uTdWzoAcBFKTVYRd2z2jDKAcuyW64rQLxa9wMreDJKo");
 
+pub const REGISTRY_SEED: &[u8] = b"registry";
+pub const CONSIGNMENT_SEED: &[u8] = b"consignment";
+pub const OFFER_SEED: &[u8] = b"offer";
+pub const PROCEEDS_SEED: &[u8] = b"proceeds";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const PAYMENT_SEED: &[u8] = b"payment";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const PARAM_CHANGE_SEED: &[u8] = b"param_change";
+pub const ROLE_SEED: &[u8] = b"role";
+pub const APPROVER_SEED: &[u8] = b"approver";
+pub const APPROVAL_SEED: &[u8] = b"approval";
+pub const QUOTE_NONCE_SEED: &[u8] = b"quote_nonce";
+pub const FILL_SEED: &[u8] = b"fill";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const BID_SEED: &[u8] = b"bid";
+pub const MAX_AUCTION_BIDS: usize = 16;
+pub const RFQ_BID_SEED: &[u8] = b"rfq_bid";
+
+/// Longest delay the desk timelock may be configured to
+pub const MAX_PARAM_TIMELOCK_SECS: i64 = 30 * 86400;
+pub const PARAM_CHANGE_GRACE_SECS: i64 = 14 * 86400; // queued changes go stale this long after their eta
+pub const MIN_QUOTE_LIFETIME_SECS: i64 = 60;
+
+/// Maximum size of the desk's multisig owner set
+pub const MAX_OWNERS: usize = 10;
+
+/// Payment mint value that denotes native SOL (lamports) rather than an SPL token
+pub const NATIVE_SOL: Pubkey = Pubkey::new_from_array([0u8; 32]);
+/// USD price used for payment mints pegged at $1 (8 decimals)
+pub const USD_PEG_8D: u64 = 100_000_000;
+
+/// Treasury authority PDA that owns a desk's token treasuries
+pub fn treasury_authority_address(desk: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED, desk.as_ref()], &ID)
+}
+
+/// TokenRegistry PDA for a mint on a desk
+pub fn token_registry_address(desk: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_SEED, desk.as_ref(), token_mint.as_ref()], &ID)
+}
+
+/// Consignment PDA for a consignment id (assigned from desk.next_consignment_id)
+pub fn consignment_address(desk: &Pubkey, consignment_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONSIGNMENT_SEED, desk.as_ref(), &consignment_id.to_le_bytes()], &ID)
+}
+
+/// Offer PDA for an offer id (assigned from desk.next_offer_id)
+pub fn offer_address(desk: &Pubkey, offer_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OFFER_SEED, desk.as_ref(), &offer_id.to_le_bytes()], &ID)
+}
+
+/// ConsignerProceeds PDA for a consigner and payment mint on a desk (NATIVE_SOL for lamports)
+pub fn consigner_proceeds_address(desk: &Pubkey, consigner: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROCEEDS_SEED, desk.as_ref(), consigner.as_ref(), payment_mint.as_ref()], &ID)
+}
+
+/// PaymentRegistry PDA for a payment mint on a desk
+pub fn payment_registry_address(desk: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PAYMENT_SEED, desk.as_ref(), payment_mint.as_ref()], &ID)
+}
+
+/// OwnerProposal PDA for a proposal id (assigned from desk.next_proposal_id)
+pub fn proposal_address(desk: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, desk.as_ref(), &proposal_id.to_le_bytes()], &ID)
+}
+
+/// PendingParamChange PDA for a queued change id (assigned from desk.next_param_change_id)
+pub fn param_change_address(desk: &Pubkey, change_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARAM_CHANGE_SEED, desk.as_ref(), &change_id.to_le_bytes()], &ID)
+}
+
+/// RoleGrant PDA for a holder's role on a desk
+pub fn role_grant_address(desk: &Pubkey, role: Role, holder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROLE_SEED, desk.as_ref(), &[role as u8], holder.as_ref()], &ID)
+}
+
+/// Approver PDA for an approver wallet on a desk
+pub fn approver_address(desk: &Pubkey, approver: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[APPROVER_SEED, desk.as_ref(), approver.as_ref()], &ID)
+}
+
+/// OfferApproval PDA recording one approver's sign-off on an offer
+pub fn offer_approval_address(offer: &Pubkey, approver: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[APPROVAL_SEED, offer.as_ref(), approver.as_ref()], &ID)
+}
+
+/// QuoteNonce PDA marking an agent quote nonce as redeemed
+pub fn quote_nonce_address(desk: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[QUOTE_NONCE_SEED, desk.as_ref(), &nonce.to_le_bytes()], &ID)
+}
+
+/// OfferFill PDA tracking one payer's tranche of a syndicated offer
+pub fn offer_fill_address(offer: &Pubkey, payer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FILL_SEED, offer.as_ref(), payer.as_ref()], &ID)
+}
+
+/// SealedBidAuction PDA for a sealed-bid consignment
+pub fn auction_address(consignment: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_SEED, consignment.as_ref()], &ID)
+}
+
+/// SealedBid PDA holding one bidder's commitment in an auction
+pub fn sealed_bid_address(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_SEED, auction.as_ref(), bidder.as_ref()], &ID)
+}
+
+/// Bid PDA for a buyer's RFQ bid
+pub fn rfq_bid_address(desk: &Pubkey, bidder: &Pubkey, bid_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RFQ_BID_SEED, desk.as_ref(), bidder.as_ref(), &bid_id.to_le_bytes()], &ID)
+}
+
 #[event]
 pub struct OfferCreated {
     pub desk: Pubkey,
@@ -18,23 +132,63 @@ pub struct OfferCreated {
     pub beneficiary: Pubkey,
     pub token_amount: u64,
     pub discount_bps: u16,
-    pub currency: u8,
+    pub payment_mint: Pubkey,
 }
 
 #[event]
 pub struct OfferApproved { pub offer: Pubkey, pub approver: Pubkey }
 
+#[event]
+pub struct OfferApprovalRecorded { pub offer: Pubkey, pub approver: Pubkey, pub approvals: u8, pub required: u8 }
+
+#[event]
+pub struct RequiredApprovalsUpdated { pub required_approvals: u8, pub large_offer_usd_8d: u64, pub large_offer_required_approvals: u8 }
+
 #[event]
 pub struct OfferCancelled { pub offer: Pubkey, pub by: Pubkey }
 
 #[event]
-pub struct OfferPaid { pub offer: Pubkey, pub payer: Pubkey, pub amount: u64, pub currency: u8 }
+pub struct OfferPaid { pub offer: Pubkey, pub payer: Pubkey, pub amount: u64, pub payment_mint: Pubkey }
 
 #[event]
-pub struct AgentCommissionPaid { pub offer: Pubkey, pub agent: Pubkey, pub amount: u64, pub currency: u8 }
+pub struct AgentCommissionPaid { pub offer: Pubkey, pub agent: Pubkey, pub amount: u64, pub payment_mint: Pubkey }
 
 #[event]
 pub struct TokensClaimed { pub offer: Pubkey, pub beneficiary: Pubkey, pub amount: u64 }
+#[event]
+pub struct OfferFilled { pub offer: Pubkey, pub payer: Pubkey, pub token_amount: u64, pub amount: u64, pub payment_mint: Pubkey, pub filled_amount: u64 }
+
+#[event]
+pub struct ConsignerProceedsCredited { pub offer: Pubkey, pub consigner: Pubkey, pub amount: u64, pub payment_mint: Pubkey }
+
+#[event]
+pub struct ConsignerProceedsWithdrawn { pub consigner: Pubkey, pub amount: u64, pub payment_mint: Pubkey }
+
+#[event]
+pub struct ConsignmentExpired { pub consignment: Pubkey, pub consigner: Pubkey, pub returned_amount: u64 }
+#[event]
+pub struct SealedBidAuctionOpened { pub consignment: Pubkey, pub auction: Pubkey, pub token_amount: u64, pub bid_end: i64, pub reveal_end: i64, pub deposit_amount: u64 }
+#[event]
+pub struct SealedBidCommitted { pub auction: Pubkey, pub bidder: Pubkey }
+#[event]
+pub struct SealedBidRevealed { pub auction: Pubkey, pub bidder: Pubkey, pub discount_bps: u16, pub token_amount: u64 }
+#[event]
+pub struct SealedBidAwarded { pub auction: Pubkey, pub bidder: Pubkey, pub offer: Pubkey, pub token_amount: u64, pub discount_bps: u16 }
+#[event]
+pub struct SealedBidDepositReleased { pub auction: Pubkey, pub bidder: Pubkey, pub amount: u64, pub forfeited: bool }
+#[event]
+pub struct AwardDepositForfeited { pub offer: Pubkey, pub consigner: Pubkey, pub amount: u64 }
+#[event]
+pub struct BidPlaced { pub desk: Pubkey, pub bid: Pubkey, pub bidder: Pubkey, pub token_mint: Pubkey, pub payment_mint: Pubkey, pub token_amount: u64, pub max_discount_bps: u16, pub max_lockup_secs: i64, pub escrow_amount: u64 }
+#[event]
+pub struct BidCancelled { pub bid: Pubkey, pub bidder: Pubkey, pub refunded: u64 }
+#[event]
+pub struct BidHit { pub bid: Pubkey, pub offer: Pubkey, pub consigner: Pubkey, pub token_amount: u64, pub discount_bps: u16, pub amount: u64, pub refunded: u64 }
+#[event]
+pub struct SealedBidAuctionFinished { pub consignment: Pubkey, pub auction: Pubkey, pub awarded_amount: u64, pub returned_amount: u64 }
+
+#[event]
+pub struct PaymentMintRegistered { pub desk: Pubkey, pub mint: Pubkey, pub treasury: Pubkey, pub is_pegged: bool }
 
 #[event]
 pub struct LimitsUpdated { pub min_usd_amount_8d: u64, pub max_token_per_order: u64, pub quote_expiry_secs: i64, pub default_unlock_delay_secs: i64, pub max_lockup_secs: i64 }
@@ -48,11 +202,60 @@ pub struct RestrictFulfillUpdated { pub enabled: bool }
 #[event]
 pub struct Paused { pub paused: bool }
 
+#[event]
+pub struct OwnershipTransferStarted { pub desk: Pubkey, pub previous_owner: Pubkey, pub pending_owner: Pubkey }
+
+#[event]
+pub struct OwnershipTransferred { pub desk: Pubkey, pub previous_owner: Pubkey, pub new_owner: Pubkey }
+
+#[event]
+pub struct OwnershipTransferCancelled { pub desk: Pubkey, pub pending_owner: Pubkey }
+
+#[event]
+pub struct OwnersUpdated { pub desk: Pubkey, pub owners: Vec<Pubkey>, pub threshold: u8 }
+
+#[event]
+pub struct ProposalCreated { pub desk: Pubkey, pub proposal: Pubkey, pub id: u64, pub proposer: Pubkey, pub expires_at: i64 }
+
+#[event]
+pub struct ProposalApproved { pub proposal: Pubkey, pub owner: Pubkey, pub approvals: u8 }
+
+#[event]
+pub struct ProposalExecuted { pub proposal: Pubkey, pub executor: Pubkey }
+
+#[event]
+pub struct ProposalCancelled { pub proposal: Pubkey, pub proposer: Pubkey }
+
+#[event]
+pub struct ApproverUpdated { pub desk: Pubkey, pub approver: Pubkey, pub max_usd_per_offer_8d: u64, pub daily_limit_usd_8d: u64, pub is_senior: bool }
+
+#[event]
+pub struct ApproverRemoved { pub desk: Pubkey, pub approver: Pubkey }
+
+#[event]
+pub struct RoleGranted { pub desk: Pubkey, pub role: Role, pub holder: Pubkey }
+
+#[event]
+pub struct RoleRevoked { pub desk: Pubkey, pub role: Role, pub holder: Pubkey }
+
+#[event]
+pub struct ParamChangeQueued { pub desk: Pubkey, pub id: u64, pub change: ParamChange, pub eta: i64 }
+
+#[event]
+pub struct ParamChangeExecuted { pub desk: Pubkey, pub id: u64 }
+
+#[event]
+pub struct ParamChangeCancelled { pub desk: Pubkey, pub id: u64 }
+
 #[allow(deprecated)]
 #[program]
 pub mod otc {
     use super::*;
 
+    /// Create a desk whose token treasuries are owned by its ["treasury", desk] PDA
+    /// There is no in-place migration: desks, offers and consignments created before treasury
+    /// PDAs have incompatible layouts and keypair addresses, so upgrading needs a fresh deployment.
+    /// Drain old treasuries with the desk keypair (their SPL owner) before upgrading, then init new desks
     pub fn init_desk(
         ctx: Context<InitDesk>,
         min_usd_amount_8d: u64,
@@ -67,8 +270,8 @@ pub mod otc {
         desk.owner = ctx.accounts.owner.key();
         desk.agent = ctx.accounts.agent.key();
         desk.usdc_mint = ctx.accounts.usdc_mint.key();
+        // Any decimals are fine: payments convert with the mint's decimals from its PaymentRegistry
         desk.usdc_decimals = ctx.accounts.usdc_mint.decimals;
-        require!(desk.usdc_decimals == 6, OtcError::UsdcDecimals);
         desk.min_usd_amount_8d = min_usd_amount_8d;
         desk.quote_expiry_secs = quote_expiry_secs;
         desk.max_price_age_secs = 3600;
@@ -92,19 +295,135 @@ pub mod otc {
         desk.max_token_per_order = u64::MAX; // No limit - each TokenRegistry has its own limits
         desk.emergency_refund_enabled = false;
         desk.emergency_refund_deadline_secs = 30 * 86400; // 30 days default
-        desk.approvers = Vec::new();
         desk.p2p_commission_bps = 25; // Default: 0.25% commission for P2P deals
+        desk.consigner_sol_owed = 0;
+        desk.treasury_authority = ctx.accounts.treasury_authority.key();
+        desk.treasury_bump = ctx.bumps.treasury_authority;
+        desk.pending_owner = Pubkey::default();
+        desk.owners = Vec::new();
+        desk.owner_threshold = 1; // single owner until set_owners configures a multisig
+        desk.next_proposal_id = 1;
+        desk.param_timelock_secs = 0; // queued changes can execute immediately until a delay is configured
+        desk.next_param_change_id = 1;
+        desk.senior_approval_usd_8d = 0; // no senior sign-off required until configured
+        desk.required_approvals = 1;
+        desk.large_offer_usd_8d = 0; // no large-offer tier until configured
+        desk.large_offer_required_approvals = 1;
         Ok(())
     }
 
-    /// Transfer desk ownership to a new owner
+    /// Start a desk ownership transfer; takes effect once new_owner calls accept_ownership
     /// Only the current owner can call this
     pub fn transfer_owner(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
         require!(new_owner != Pubkey::default(), OtcError::BadState);
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::TransferOwner { new_owner })?;
+        let desk_key = ctx.accounts.desk.key();
+        let desk = &mut ctx.accounts.desk;
+        desk.pending_owner = new_owner;
+        emit!(OwnershipTransferStarted { desk: desk_key, previous_owner: desk.owner, pending_owner: new_owner });
+        Ok(())
+    }
+
+    /// Complete an ownership transfer; must be signed by the pending owner
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let desk = &mut ctx.accounts.desk;
+        let previous_owner = desk.owner;
+        desk.owner = ctx.accounts.pending_owner.key();
+        desk.pending_owner = Pubkey::default();
+        emit!(OwnershipTransferred { desk: desk_key, previous_owner, new_owner: desk.owner });
+        Ok(())
+    }
+
+    /// Withdraw a pending ownership transfer before it is accepted
+    pub fn cancel_ownership_transfer(ctx: Context<OwnerAction>) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::CancelOwnershipTransfer)?;
+        let desk_key = ctx.accounts.desk.key();
+        let desk = &mut ctx.accounts.desk;
+        require!(desk.pending_owner != Pubkey::default(), OtcError::BadState);
+        let pending_owner = desk.pending_owner;
+        desk.pending_owner = Pubkey::default();
+        emit!(OwnershipTransferCancelled { desk: desk_key, pending_owner });
+        Ok(())
+    }
+
+    /// Configure the M-of-N owner set for sensitive actions
+    /// threshold <= 1 keeps single-owner mode; changing an active multisig requires a proposal
+    pub fn set_owners(ctx: Context<OwnerAction>, owners: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::SetOwners { owners: owners.clone(), threshold })?;
+        require!(owners.len() <= MAX_OWNERS, OtcError::TooManyOwners);
+        require!(threshold as usize <= owners.len(), OtcError::InvalidThreshold);
+        for (i, owner) in owners.iter().enumerate() {
+            require!(*owner != Pubkey::default() && !owners[..i].contains(owner), OtcError::BadState);
+        }
+        let desk_key = ctx.accounts.desk.key();
+        let desk = &mut ctx.accounts.desk;
+        desk.owners = owners;
+        desk.owner_threshold = threshold;
+        emit!(OwnersUpdated { desk: desk_key, owners: desk.owners.clone(), threshold });
+        Ok(())
+    }
+
+    /// Propose a sensitive action; the proposer's approval is recorded immediately
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction, expires_in_secs: i64) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let proposal_key = ctx.accounts.proposal.key();
+        let proposer = ctx.accounts.proposer.key();
         let desk = &mut ctx.accounts.desk;
-        let old_owner = desk.owner;
-        desk.owner = new_owner;
-        msg!("Desk ownership transferred from {} to {}", old_owner, new_owner);
+        require!(desk.owner_threshold > 1, OtcError::BadState);
+        require!(desk.owners.contains(&proposer), OtcError::NotOwner);
+        require!(expires_in_secs > 0, OtcError::AmountRange);
+        if let ProposalAction::SetOwners { owners, .. } = &action {
+            require!(owners.len() <= MAX_OWNERS, OtcError::TooManyOwners);
+        }
+
+        let proposal_id = desk.next_proposal_id;
+        desk.next_proposal_id = proposal_id.checked_add(1).ok_or(OtcError::Overflow)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.desk = desk_key;
+        proposal.id = proposal_id;
+        proposal.proposer = proposer;
+        proposal.action = action;
+        proposal.approvals = vec![proposer];
+        proposal.created_at = now;
+        proposal.expires_at = now.checked_add(expires_in_secs).ok_or(OtcError::Overflow)?;
+        proposal.executed = false;
+        proposal.cancelled = false;
+        emit!(ProposalCreated { desk: desk_key, proposal: proposal_key, id: proposal_id, proposer, expires_at: proposal.expires_at });
+        Ok(())
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, _proposal_id: u64) -> Result<()> {
+        let desk = &ctx.accounts.desk;
+        let owner = ctx.accounts.owner.key();
+        require!(desk.owners.contains(&owner), OtcError::NotOwner);
+        let proposal_key = ctx.accounts.proposal.key();
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed && !proposal.cancelled, OtcError::BadState);
+        require!(Clock::get()?.unix_timestamp <= proposal.expires_at, OtcError::Expired);
+        require!(!proposal.approvals.contains(&owner), OtcError::AlreadyApproved);
+        require!(proposal.approvals.len() < MAX_OWNERS, OtcError::TooManyOwners);
+        proposal.approvals.push(owner);
+        emit!(ProposalApproved { proposal: proposal_key, owner, approvals: proposal.approvals.len() as u8 });
+        Ok(())
+    }
+
+    /// Withdraw an unexecuted proposal; only its proposer can cancel it
+    pub fn cancel_proposal(ctx: Context<ProposerAction>, _proposal_id: u64) -> Result<()> {
+        let proposal_key = ctx.accounts.proposal.key();
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed && !proposal.cancelled, OtcError::BadState);
+        proposal.cancelled = true;
+        emit!(ProposalCancelled { proposal: proposal_key, proposer: proposal.proposer });
+        Ok(())
+    }
+
+    /// Close an executed or cancelled proposal and return its rent to the proposer
+    pub fn close_proposal(ctx: Context<CloseProposal>, _proposal_id: u64) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require!(proposal.executed || proposal.cancelled, OtcError::BadState);
         Ok(())
     }
 
@@ -124,12 +443,7 @@ pub mod otc {
         registry.decimals = ctx.accounts.token_mint.decimals;
         registry.price_feed_id = price_feed_id;
         registry.pool_address = pool_address;
-        registry.pool_type = match pool_type {
-            1 => PoolType::Raydium,
-            2 => PoolType::Orca,
-            3 => PoolType::PumpSwap,
-            _ => PoolType::None,
-        };
+        registry.pool_type = pool_type_from_u8(pool_type);
         registry.is_active = true;
         registry.token_usd_price_8d = 0;
         registry.prices_updated_at = 0;
@@ -141,6 +455,10 @@ pub mod otc {
         registry.twap_last_price = 0;
         registry.max_twap_deviation_bps = 0; // Disabled by default
         registry.min_update_interval_secs = 60; // Minimum 1 minute between updates
+        // Treasury inventory accounting
+        registry.desk_owned_amount = 0;
+        registry.consigned_amount = 0;
+        registry.reserved_amount = 0;
         
         Ok(())
     }
@@ -161,15 +479,43 @@ pub mod otc {
         is_private: bool,
         max_price_volatility_bps: u16,
         max_time_to_execute_secs: i64,
+        vesting_cliff_days: u32,
+        vesting_duration_days: u32,
+        vesting_period_days: u32,
+        max_quote_lifetime_secs: i64,
+        is_dutch_auction: bool,
+        auction_duration_secs: i64,
+        is_sealed_bid: bool,
     ) -> Result<()> {
         let desk = &mut ctx.accounts.desk;
         require!(!desk.paused, OtcError::Paused);
+        require!(ctx.accounts.token_registry.is_active, OtcError::BadState);
         require!(amount > 0, OtcError::AmountRange);
         require!(min_deal_amount <= max_deal_amount, OtcError::AmountRange);
+        require!(min_deal_amount <= amount, OtcError::AmountRange);
+        // Non-fractionalized consignments sell as one block, so the whole amount must be a valid deal size
+        if !is_fractionalized {
+            require!(amount <= max_deal_amount, OtcError::AmountRange);
+        }
         require!(min_discount_bps <= max_discount_bps, OtcError::Discount);
         require!(max_discount_bps <= 10000, OtcError::Discount); // Max 100% discount
         require!(fixed_discount_bps <= 10000, OtcError::Discount); // Max 100% discount
         require!(min_lockup_days <= max_lockup_days, OtcError::LockupTooLong);
+        // Every consignment offer is price-protected; 0 would read as "no limit" at fulfilment
+        require!(max_price_volatility_bps > 0 && max_price_volatility_bps <= 10000, OtcError::AmountRange);
+        require!(max_time_to_execute_secs >= 0, OtcError::AmountRange); // 0 = never expires
+        // 0 = desk.quote_expiry_secs applies alone
+        require!(max_quote_lifetime_secs == 0 || max_quote_lifetime_secs >= MIN_QUOTE_LIFETIME_SECS, OtcError::AmountRange);
+        // Dutch auctions are P2P listings whose discount decays from min to max over the auction
+        if is_dutch_auction {
+            require!(!is_negotiable, OtcError::BadState);
+            require!(auction_duration_secs > 0, OtcError::AmountRange);
+        } else {
+            require!(auction_duration_secs == 0, OtcError::AmountRange);
+        }
+        // Sealed-bid blocks are only sold through open_sealed_bid_auction
+        require!(!is_sealed_bid || (!is_negotiable && !is_dutch_auction), OtcError::BadState);
+        validate_vesting(vesting_cliff_days as i64 * 86400, vesting_duration_days as i64 * 86400, vesting_period_days as i64 * 86400)?;
 
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.consigner_token_ata.to_account_info(),
@@ -179,6 +525,8 @@ pub mod otc {
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
         transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        let registry = &mut ctx.accounts.token_registry;
+        registry.consigned_amount = registry.consigned_amount.checked_add(amount).ok_or(OtcError::Overflow)?;
 
         let consignment_id = desk.next_consignment_id;
         desk.next_consignment_id = consignment_id.checked_add(1).ok_or(OtcError::Overflow)?;
@@ -203,14 +551,23 @@ pub mod otc {
         consignment.is_private = is_private;
         consignment.max_price_volatility_bps = max_price_volatility_bps;
         consignment.max_time_to_execute_secs = max_time_to_execute_secs;
+        consignment.vesting_cliff_days = vesting_cliff_days;
+        consignment.vesting_duration_days = vesting_duration_days;
+        consignment.vesting_period_days = vesting_period_days;
+        consignment.max_quote_lifetime_secs = max_quote_lifetime_secs;
+        consignment.is_dutch_auction = is_dutch_auction;
+        consignment.auction_duration_secs = auction_duration_secs;
+        consignment.is_sealed_bid = is_sealed_bid;
+        consignment.withdrawn = false;
         consignment.is_active = true;
         consignment.created_at = Clock::get()?.unix_timestamp;
 
         Ok(())
     }
 
-    pub fn set_prices(ctx: Context<OnlyOwnerDesk>, token_usd_8d: u64, sol_usd_8d: u64, _updated_at: i64, max_age: i64) -> Result<()> {
-        require!(max_age >= 0, OtcError::AmountRange);
+    /// Price staleness (max_price_age_secs) changes through the timelock with ParamChange::SetMaxPriceAge
+    pub fn set_prices(ctx: Context<DeskRoleAction>, token_usd_8d: u64, sol_usd_8d: u64, _updated_at: i64) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::PriceSetter)?;
         // Add price bounds checking like EVM version
         require!(token_usd_8d > 0 && token_usd_8d <= 1_000_000_000_000, OtcError::BadPrice); // Max $10,000 per token (8 decimals)
         require!(sol_usd_8d >= 1_000_000 && sol_usd_8d <= 10_000_000_000_000, OtcError::BadPrice); // $0.01 - $100,000
@@ -220,48 +577,7 @@ pub mod otc {
         desk.token_usd_price_8d = token_usd_8d;
         desk.sol_usd_price_8d = sol_usd_8d;
         desk.prices_updated_at = now;
-        desk.max_price_age_secs = max_age;
-        emit!(PricesUpdated { token_usd_8d, sol_usd_8d, updated_at: now, max_age });
-        Ok(())
-    }
-
-    pub fn set_pyth_feeds(ctx: Context<OnlyOwnerDesk>, token_feed_id: [u8; 32], sol_feed_id: [u8; 32]) -> Result<()> {
-        let desk = &mut ctx.accounts.desk;
-        desk.token_price_feed_id = token_feed_id;
-        desk.sol_price_feed_id = sol_feed_id;
-        Ok(())
-    }
-
-    pub fn set_token_oracle_feed(ctx: Context<SetTokenOracleFeed>, feed_id: [u8; 32]) -> Result<()> {
-        let registry = &mut ctx.accounts.token_registry;
-        registry.price_feed_id = feed_id;
-        Ok(())
-    }
-
-    /// Set/update the pool address and type for automatic price updates
-    /// Can be called by owner OR the original registrant (permissionless for the registrant)
-    pub fn set_token_pool_config(
-        ctx: Context<SetTokenPoolConfig>,
-        pool_address: Pubkey,
-        pool_type: u8, // 0=None, 1=Raydium, 2=Orca, 3=PumpSwap
-    ) -> Result<()> {
-        let registry = &mut ctx.accounts.token_registry;
-        let desk = &ctx.accounts.desk;
-        let signer = &ctx.accounts.signer;
-        
-        // Allow owner OR the original registrant to update
-        require!(
-            signer.key() == desk.owner || signer.key() == registry.registered_by,
-            OtcError::NotOwner
-        );
-        
-        registry.pool_address = pool_address;
-        registry.pool_type = match pool_type {
-            1 => PoolType::Raydium,
-            2 => PoolType::Orca,
-            3 => PoolType::PumpSwap,
-            _ => PoolType::None,
-        };
+        emit!(PricesUpdated { token_usd_8d, sol_usd_8d, updated_at: now, max_age: desk.max_price_age_secs });
         Ok(())
     }
 
@@ -269,6 +585,7 @@ pub mod otc {
     /// Production should primarily use Pyth oracle or on-chain pool pricing
     /// NOTE: This function should be restricted via access control in production
     pub fn set_manual_token_price(ctx: Context<SetManualTokenPrice>, price_8d: u64) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::PriceSetter)?;
         let registry = &mut ctx.accounts.token_registry;
         // Price bounds: $0.00000001 to $10,000 (8 decimals)
         require!(price_8d > 0 && price_8d <= 1_000_000_000_000, OtcError::BadPrice);
@@ -309,24 +626,6 @@ pub mod otc {
         Ok(())
     }
 
-    /// Configure pool oracle security settings (owner only)
-    pub fn configure_pool_oracle(
-        ctx: Context<ConfigurePoolOracle>,
-        min_liquidity: u64,
-        max_twap_deviation_bps: u16,
-        min_update_interval_secs: i64,
-    ) -> Result<()> {
-        let registry = &mut ctx.accounts.token_registry;
-        require!(min_update_interval_secs >= 30, OtcError::AmountRange); // Minimum 30 seconds
-        require!(max_twap_deviation_bps <= 5000, OtcError::AmountRange); // Max 50% deviation
-        
-        registry.min_liquidity = min_liquidity;
-        registry.max_twap_deviation_bps = max_twap_deviation_bps;
-        registry.min_update_interval_secs = min_update_interval_secs;
-        Ok(())
-    }
-
-    /// Update token price from AMM pool with EMA smoothing and manipulation resistance
     pub fn update_token_price_from_pool(
         ctx: Context<UpdateTokenPriceFromPool>,
     ) -> Result<()> {
@@ -365,8 +664,8 @@ pub mod otc {
             require!(amount_b >= registry.min_liquidity, OtcError::InsufficientLiquidity);
         }
         
-        // Calculate spot price: vault_a = Token, vault_b = Quote (USDC, 6 decimals)
-        let quote_decimals = 6u32;
+        // Calculate spot price: vault_a = Token, vault_b = Quote (a USD stablecoin, at its mint's decimals)
+        let quote_decimals = ctx.accounts.quote_mint.decimals as u32;
         let token_decimals = registry.decimals as u32;
         
         // price = amount_b * 10^8 * 10^token_dec / (amount_a * 10^quote_dec)
@@ -401,11 +700,7 @@ pub mod otc {
                 let ema_price = u64::try_from(new_ema).map_err(|_| OtcError::Overflow)?;
                 
                 // Check deviation from EMA
-                let deviation = if spot_price_8d > ema_price {
-                    spot_price_8d - ema_price
-                } else {
-                    ema_price - spot_price_8d
-                };
+                let deviation = spot_price_8d.abs_diff(ema_price);
                 
                 let max_deviation = (ema_price as u128)
                     .checked_mul(registry.max_twap_deviation_bps as u128)
@@ -435,6 +730,8 @@ pub mod otc {
         ctx: Context<UpdateTokenPriceFromPumpswap>,
         sol_usd_price_8d: u64, // SOL/USD price with 8 decimals (from Pyth or other source)
     ) -> Result<()> {
+        // The SOL price is caller-supplied, so only trusted price keepers may push it
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::PriceSetter)?;
         let registry = &mut ctx.accounts.token_registry;
         require!(registry.pool_address != Pubkey::default(), OtcError::FeedNotConfigured);
         require!(registry.pool_type == PoolType::PumpSwap, OtcError::BadState);
@@ -514,54 +811,173 @@ pub mod otc {
         Ok(())
     }
 
-    pub fn set_limits(ctx: Context<OnlyOwnerDesk>, min_usd_amount_8d: u64, max_token_per_order: u64, quote_expiry_secs: i64, default_unlock_delay_secs: i64, max_lockup_secs: i64) -> Result<()> {
-        require!(min_usd_amount_8d > 0, OtcError::AmountRange);
-        require!(max_token_per_order > 0, OtcError::AmountRange);
-        require!(quote_expiry_secs >= 60, OtcError::AmountRange); // Minimum 60 seconds to prevent race conditions
-        require!(max_lockup_secs >= 0, OtcError::AmountRange);
-        require!(default_unlock_delay_secs >= 0 && default_unlock_delay_secs <= max_lockup_secs, OtcError::AmountRange);
+    /// Lengthen the parameter timelock immediately; shortening it must be queued
+    pub fn set_param_timelock(ctx: Context<OwnerAction>, delay_secs: i64) -> Result<()> {
+        require!(delay_secs >= ctx.accounts.desk.param_timelock_secs, OtcError::TimelockRequired);
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::SetParamTimelock { delay_secs })?;
+        apply_desk_param_change(&mut ctx.accounts.desk, &ParamChange::SetParamTimelock { delay_secs })
+    }
+
+    /// Queue a timelocked parameter change; executable once desk.param_timelock_secs has elapsed
+    /// and for PARAM_CHANGE_GRACE_SECS after that. Economic and oracle parameters only change this way
+    pub fn queue_param_change(ctx: Context<QueueParamChange>, change: ParamChange) -> Result<()> {
+        validate_param_change(&change)?;
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::QueueParamChange { change: change.clone() })?;
+        let desk_key = ctx.accounts.desk.key();
         let desk = &mut ctx.accounts.desk;
-        desk.min_usd_amount_8d = min_usd_amount_8d;
-        desk.max_token_per_order = max_token_per_order;
-        desk.quote_expiry_secs = quote_expiry_secs;
-        desk.default_unlock_delay_secs = default_unlock_delay_secs;
-        desk.max_lockup_secs = max_lockup_secs;
-        emit!(LimitsUpdated { min_usd_amount_8d, max_token_per_order, quote_expiry_secs, default_unlock_delay_secs, max_lockup_secs });
+        let change_id = desk.next_param_change_id;
+        desk.next_param_change_id = change_id.checked_add(1).ok_or(OtcError::Overflow)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let pending = &mut ctx.accounts.pending_change;
+        pending.desk = desk_key;
+        pending.id = change_id;
+        pending.change = change.clone();
+        pending.queued_at = now;
+        pending.eta = now.checked_add(desk.param_timelock_secs).ok_or(OtcError::Overflow)?;
+        emit!(ParamChangeQueued { desk: desk_key, id: change_id, change, eta: pending.eta });
+        Ok(())
+    }
+
+    /// Apply a queued parameter change after its delay (owner, or an approved proposal in multisig mode);
+    /// closes the queue entry. A change not executed within the grace window is stale and can only be cancelled
+    pub fn execute_param_change(ctx: Context<ExecuteParamChange>, change_id: u64) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::ExecuteParamChange { change_id })?;
+        let pending = &ctx.accounts.pending_change;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.eta, OtcError::TimelockNotElapsed);
+        require!(now <= pending.eta.checked_add(PARAM_CHANGE_GRACE_SECS).ok_or(OtcError::Overflow)?, OtcError::Expired);
+        let change = pending.change.clone();
+        match &change {
+            ParamChange::ConfigurePoolOracle { token_registry, .. }
+            | ParamChange::SetTokenOracleFeed { token_registry, .. }
+            | ParamChange::SetTokenPoolConfig { token_registry, .. } => {
+                let registry = ctx.accounts.token_registry.as_mut().ok_or(OtcError::BadState)?;
+                require!(registry.key() == *token_registry, OtcError::BadState);
+                apply_registry_param_change(registry, &change)?;
+            }
+            _ => apply_desk_param_change(&mut ctx.accounts.desk, &change)?,
+        }
+        emit!(ParamChangeExecuted { desk: ctx.accounts.desk.key(), id: change_id });
+        Ok(())
+    }
+
+    /// Drop a queued parameter change (owner, or an approved proposal in multisig mode); closes the queue entry
+    pub fn cancel_param_change(ctx: Context<CancelParamChange>, change_id: u64) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::CancelParamChange { change_id })?;
+        emit!(ParamChangeCancelled { desk: ctx.accounts.desk.key(), id: change_id });
         Ok(())
     }
 
-    pub fn set_agent(ctx: Context<OnlyOwnerDesk>, new_agent: Pubkey) -> Result<()> {
+    pub fn set_agent(ctx: Context<OwnerAction>, new_agent: Pubkey) -> Result<()> {
         require!(new_agent != Pubkey::default(), OtcError::BadState);
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::SetAgent { new_agent })?;
         ctx.accounts.desk.agent = new_agent;
         Ok(())
     }
 
-    pub fn set_restrict_fulfill(ctx: Context<OnlyOwnerDesk>, enabled: bool) -> Result<()> {
+    pub fn set_restrict_fulfill(ctx: Context<ApproverAdminAction>, enabled: bool) -> Result<()> {
+        authorize_approver_admin_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, ProposalAction::SetRestrictFulfill { enabled })?;
         ctx.accounts.desk.restrict_fulfill = enabled;
         emit!(RestrictFulfillUpdated { enabled });
         Ok(())
     }
 
-    pub fn pause(ctx: Context<OnlyOwnerDesk>) -> Result<()> {
+    pub fn pause(ctx: Context<DeskRoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::Pauser)?;
         ctx.accounts.desk.paused = true;
         emit!(Paused { paused: true });
         Ok(())
     }
 
-    pub fn unpause(ctx: Context<OnlyOwnerDesk>) -> Result<()> {
+    pub fn unpause(ctx: Context<DeskRoleAction>) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::Pauser)?;
         ctx.accounts.desk.paused = false;
         emit!(Paused { paused: false });
         Ok(())
     }
 
-    pub fn set_approver(ctx: Context<OnlyOwnerDesk>, who: Pubkey, allowed: bool) -> Result<()> {
-        let approvers = &mut ctx.accounts.desk.approvers;
-        if allowed {
-            if !approvers.contains(&who) {
-                require!(approvers.len() < 32, OtcError::TooManyApprovers);
-                approvers.push(who);
-            }
-        } else if let Some(i) = approvers.iter().position(|x| *x == who) { approvers.remove(i); }
+    /// Create or update an approver and its USD limits (owner or ApproverAdmin, or an approved proposal in multisig mode)
+    pub fn set_approver(
+        ctx: Context<SetApprover>,
+        approver: Pubkey,
+        max_usd_per_offer_8d: u64,
+        daily_limit_usd_8d: u64,
+        is_senior: bool,
+    ) -> Result<()> {
+        require!(approver != Pubkey::default(), OtcError::BadState);
+        require!(max_usd_per_offer_8d > 0 && max_usd_per_offer_8d <= daily_limit_usd_8d, OtcError::AmountRange);
+        let action = ProposalAction::SetApprover { approver, max_usd_per_offer_8d, daily_limit_usd_8d, is_senior };
+        authorize_approver_admin_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, action)?;
+        let desk_key = ctx.accounts.desk.key();
+        let record = &mut ctx.accounts.approver_account;
+        // Usage for the current day is preserved when limits change
+        record.desk = desk_key;
+        record.approver = approver;
+        record.max_usd_per_offer_8d = max_usd_per_offer_8d;
+        record.daily_limit_usd_8d = daily_limit_usd_8d;
+        record.is_senior = is_senior;
+        emit!(ApproverUpdated { desk: desk_key, approver, max_usd_per_offer_8d, daily_limit_usd_8d, is_senior });
+        Ok(())
+    }
+
+    /// Remove an approver and close its account (owner or ApproverAdmin, or an approved proposal in multisig mode)
+    pub fn remove_approver(ctx: Context<RemoveApprover>, approver: Pubkey) -> Result<()> {
+        authorize_approver_admin_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, ProposalAction::RemoveApprover { approver })?;
+        emit!(ApproverRemoved { desk: ctx.accounts.desk.key(), approver });
+        Ok(())
+    }
+
+    /// Number of distinct approvals a negotiated offer needs, with an optional higher tier
+    /// for offers worth at least large_offer_usd_8d (0 disables the tier)
+    pub fn set_required_approvals(
+        ctx: Context<ApproverAdminAction>,
+        required_approvals: u8,
+        large_offer_usd_8d: u64,
+        large_offer_required_approvals: u8,
+    ) -> Result<()> {
+        require!(required_approvals >= 1, OtcError::AmountRange);
+        require!(large_offer_required_approvals >= required_approvals, OtcError::AmountRange);
+        authorize_approver_admin_action(
+            &ctx.accounts.desk,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.role,
+            &mut ctx.accounts.proposal,
+            ProposalAction::SetRequiredApprovals { required_approvals, large_offer_usd_8d, large_offer_required_approvals },
+        )?;
+        let desk = &mut ctx.accounts.desk;
+        desk.required_approvals = required_approvals;
+        desk.large_offer_usd_8d = large_offer_usd_8d;
+        desk.large_offer_required_approvals = large_offer_required_approvals;
+        emit!(RequiredApprovalsUpdated { required_approvals, large_offer_usd_8d, large_offer_required_approvals });
+        Ok(())
+    }
+
+    /// Offers worth at least this much (USD, 8 decimals) need a senior approver; 0 disables
+    pub fn set_senior_approval_usd(ctx: Context<ApproverAdminAction>, usd_8d: u64) -> Result<()> {
+        authorize_approver_admin_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, ProposalAction::SetSeniorApprovalUsd { usd_8d })?;
+        ctx.accounts.desk.senior_approval_usd_8d = usd_8d;
+        Ok(())
+    }
+
+    /// Grant a named role to a holder (owner, or an approved proposal in multisig mode)
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        require!(holder != Pubkey::default(), OtcError::BadState);
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::GrantRole { role, holder })?;
+        let desk_key = ctx.accounts.desk.key();
+        let grant = &mut ctx.accounts.role_grant;
+        grant.desk = desk_key;
+        grant.role = role;
+        grant.holder = holder;
+        grant.granted_at = Clock::get()?.unix_timestamp;
+        emit!(RoleGranted { desk: desk_key, role, holder });
+        Ok(())
+    }
+
+    /// Revoke a role and close its grant account (owner, or an approved proposal in multisig mode)
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role, holder: Pubkey) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::RevokeRole { role, holder })?;
+        emit!(RoleRevoked { desk: ctx.accounts.desk.key(), role, holder });
         Ok(())
     }
 
@@ -579,8 +995,9 @@ pub mod otc {
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
         transfer_checked(cpi_ctx, amount, ctx.accounts.token_registry.decimals)?;
-        // Note: We don't track desk.token_deposited since all tokens are equal
-        // and we use TokenRegistry per token. Treasury balance is the source of truth.
+        // Desk-owned inventory is tracked per token on the TokenRegistry
+        let registry = &mut ctx.accounts.token_registry;
+        registry.desk_owned_amount = registry.desk_owned_amount.checked_add(amount).ok_or(OtcError::Overflow)?;
         Ok(())
     }
 
@@ -590,19 +1007,24 @@ pub mod otc {
         ctx: Context<CreateOffer>,
         token_amount: u64,
         discount_bps: u16,
-        currency: u8,
+        payment_mint: Pubkey,
         lockup_secs: i64,
+        vesting_cliff_secs: i64,
+        vesting_duration_secs: i64,
+        vesting_period_secs: i64,
+        quote_lifetime_secs: i64,
     ) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
         let desk = &mut ctx.accounts.desk;
-        let registry = &ctx.accounts.token_registry;
+        let registry = &mut ctx.accounts.token_registry;
         
         require!(!desk.paused, OtcError::Paused);
         require!(registry.is_active, OtcError::BadState);
-        require!(currency == 0 || currency == 1, OtcError::UnsupportedCurrency);
         require!(token_amount > 0, OtcError::AmountRange);
         require!(discount_bps <= 10000, OtcError::Discount); // Max 100% discount
         
         let now = Clock::get()?.unix_timestamp;
+        let payment_usd_price_8d = payment_price_for_offer(desk, desk_key, payment_mint, &ctx.accounts.payment_registry, now)?;
         
         // Use TokenRegistry for price
         require!(registry.token_usd_price_8d > 0, OtcError::NoPrice);
@@ -615,6 +1037,13 @@ pub mod otc {
         require!(total_usd_disc >= desk.min_usd_amount_8d, OtcError::MinUsd);
 
         require!(lockup_secs >= desk.default_unlock_delay_secs && lockup_secs <= desk.max_lockup_secs, OtcError::AmountRange);
+        validate_vesting(vesting_cliff_secs, vesting_duration_secs, vesting_period_secs)?;
+        // Tokens must be fully vested within the desk's maximum lockup horizon
+        require!(lockup_secs.checked_add(vesting_duration_secs).ok_or(OtcError::Overflow)? <= desk.max_lockup_secs, OtcError::LockupTooLong);
+
+        // Reserve desk-owned inventory so concurrent offers cannot oversubscribe the treasury
+        registry.desk_owned_amount = registry.desk_owned_amount.checked_sub(token_amount).ok_or(OtcError::InsuffInv)?;
+        registry.reserved_amount = registry.reserved_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
 
         let offer_id = desk.next_offer_id;
         desk.next_offer_id = offer_id.checked_add(1).ok_or(OtcError::Overflow)?;
@@ -633,8 +1062,8 @@ pub mod otc {
         offer.unlock_time = now.checked_add(lockup_secs).ok_or(OtcError::Overflow)?;
         offer.price_usd_per_token_8d = registry.token_usd_price_8d;
         offer.max_price_deviation_bps = 0; 
-        offer.sol_usd_price_8d = if currency == 0 { desk.sol_usd_price_8d } else { 0 };
-        offer.currency = currency;
+        offer.payment_usd_price_8d = payment_usd_price_8d;
+        offer.payment_mint = payment_mint;
         offer.approved = false;
         offer.paid = false;
         offer.fulfilled = false;
@@ -642,6 +1071,18 @@ pub mod otc {
         offer.payer = Pubkey::default();
         offer.amount_paid = 0;
         offer.agent_commission_bps = 0; // Direct offers have no agent commission
+        offer.consigner_proceeds = 0;
+        offer.vesting_start = offer.unlock_time;
+        offer.vesting_cliff_secs = vesting_cliff_secs;
+        offer.vesting_duration_secs = vesting_duration_secs;
+        offer.vesting_period_secs = vesting_period_secs;
+        offer.claimed_amount = 0;
+        offer.approval_count = 0;
+        offer.expires_at = offer_expires_at(desk, 0, quote_lifetime_secs, now)?;
+        offer.filled_amount = 0;
+        offer.fill_count = 0;
+        offer.syndicated = false;
+        offer.award_deposit = 0;
 
         emit!(OfferCreated {
             desk: offer.desk,
@@ -649,7 +1090,7 @@ pub mod otc {
             beneficiary: offer.beneficiary,
             token_amount,
             discount_bps,
-            currency
+            payment_mint
         });
         Ok(())
     }
@@ -660,151 +1101,606 @@ pub mod otc {
     /// Commission is paid to desk.agent from seller proceeds at fulfillment
     pub fn create_offer_from_consignment(
         ctx: Context<CreateOfferFromConsignment>,
-        consignment_id: u64,
+        _consignment_id: u64,
         token_amount: u64,
         discount_bps: u16,
-        currency: u8,
+        payment_mint: Pubkey,
         lockup_secs: i64,
         agent_commission_bps: u16,
+        vesting_cliff_secs: i64,
+        vesting_duration_secs: i64,
+        vesting_period_secs: i64,
+        quote_lifetime_secs: i64,
     ) -> Result<()> {
-        let desk_key = ctx.accounts.desk.key();
-        let desk = &mut ctx.accounts.desk;
-        require!(!desk.paused, OtcError::Paused);
-        require!(currency == 0 || currency == 1, OtcError::UnsupportedCurrency);
-
-        let consignment = &mut ctx.accounts.consignment;
-        require!(consignment.is_active, OtcError::BadState);
-        
-        // Enforce is_private: only consigner, owner, agent, or approvers can create offers
-        if consignment.is_private {
-            let caller = ctx.accounts.beneficiary.key();
-            require!(
-                caller == consignment.consigner || 
-                caller == desk.owner || 
-                caller == desk.agent || 
-                desk.approvers.contains(&caller),
-                OtcError::FulfillRestricted
-            );
-        }
-        
-        require!(token_amount >= consignment.min_deal_amount && token_amount <= consignment.max_deal_amount, OtcError::AmountRange);
-        require!(token_amount <= consignment.remaining_amount, OtcError::InsuffInv);
-
-        // Determine effective commission for the offer
-        let effective_commission_bps: u16 = if consignment.is_negotiable {
-            require!(discount_bps >= consignment.min_discount_bps && discount_bps <= consignment.max_discount_bps, OtcError::Discount);
-            let lockup_days = lockup_secs / 86400;
-            require!(lockup_days >= consignment.min_lockup_days as i64 && lockup_days <= consignment.max_lockup_days as i64, OtcError::LockupTooLong);
-            // Negotiated deals: commission must be 25-150 bps (0.25% - 1.5%)
-            require!(agent_commission_bps >= 25 && agent_commission_bps <= 150, OtcError::CommissionRange);
-            agent_commission_bps
-        } else {
-            require!(discount_bps == consignment.fixed_discount_bps, OtcError::Discount);
-            let lockup_days = lockup_secs / 86400;
-            require!(lockup_days == consignment.fixed_lockup_days as i64, OtcError::LockupTooLong);
-            // P2P deals: use the configured p2p_commission_bps (default 0.25%)
-            // agent_commission_bps parameter is ignored for P2P - uses desk-wide setting
-            desk.p2p_commission_bps
+        let terms = OfferTerms {
+            token_amount,
+            discount_bps,
+            payment_mint,
+            lockup_secs,
+            agent_commission_bps,
+            vesting_cliff_secs,
+            vesting_duration_secs,
+            vesting_period_secs,
+            quote_lifetime_secs,
         };
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let accounts = &mut *ctx.accounts;
+        open_consignment_offer(
+            &mut accounts.desk,
+            &mut accounts.consignment,
+            &accounts.token_registry,
+            &accounts.payment_registry,
+            &accounts.approver_account,
+            &mut accounts.offer,
+            beneficiary,
+            &terms,
+            false,
+        )
+    }
 
-        // Use registry price for multi-token support
-        let registry = &ctx.accounts.token_registry;
-        require!(registry.token_mint == consignment.token_mint, OtcError::BadState); // Ensure registry matches consignment
-        
-        let price_8d = registry.token_usd_price_8d;
-        require!(price_8d > 0, OtcError::NoPrice);
-        
+    /// Create a pre-approved consignment offer from a quote signed off-chain by desk.agent
+    /// The transaction must carry an ed25519 verification of the borsh-encoded quote
+    /// immediately before this instruction; the quote nonce can only be redeemed once
+    pub fn create_offer_from_quote(ctx: Context<CreateOfferFromQuote>, quote: AgentQuote) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let beneficiary = ctx.accounts.beneficiary.key();
+        require!(quote.desk == desk_key && quote.beneficiary == beneficiary, OtcError::BadState);
         let now = Clock::get()?.unix_timestamp;
-        // Check registry price age
-        if registry.prices_updated_at > 0 {
-            require!(now - registry.prices_updated_at <= desk.max_price_age_secs, OtcError::StalePrice);
-        }
-
-        // Check implied USD value meets minimum
-        let total_usd_disc = calc_discounted_usd(token_amount, price_8d, registry.decimals, discount_bps)?;
-        require!(total_usd_disc >= desk.min_usd_amount_8d, OtcError::MinUsd);
+        require!(now <= quote.expires_at, OtcError::Expired);
+        let message = anchor_lang::prelude::borsh::to_vec(&quote).map_err(|_| OtcError::InvalidQuoteSignature)?;
+        verify_agent_signature(&ctx.accounts.instructions, &ctx.accounts.desk.agent, &message)?;
 
-        consignment.remaining_amount = consignment.remaining_amount.checked_sub(token_amount).ok_or(OtcError::Overflow)?;
-        if consignment.remaining_amount == 0 {
-            consignment.is_active = false;
-        }
+        let quote_nonce = &mut ctx.accounts.quote_nonce;
+        quote_nonce.desk = desk_key;
+        quote_nonce.nonce = quote.nonce;
+        quote_nonce.offer = ctx.accounts.offer.key();
+        quote_nonce.used_at = now;
 
-        let offer_id = desk.next_offer_id;
-        desk.next_offer_id = offer_id.checked_add(1).ok_or(OtcError::Overflow)?;
+        let approval = &mut ctx.accounts.offer_approval;
+        approval.offer = ctx.accounts.offer.key();
+        approval.approver = ctx.accounts.desk.agent;
+        approval.approved_at = now;
 
-        let offer_key = ctx.accounts.offer.key();
-        let beneficiary_key = ctx.accounts.beneficiary.key();
-        
-        // Non-negotiable offers are auto-approved for P2P (permissionless)
-        // Negotiable offers require agent/approver approval
-        let auto_approved = !consignment.is_negotiable;
-        
-        let offer = &mut ctx.accounts.offer;
-        
-        offer.desk = desk_key;
-        offer.consignment_id = consignment_id;
-        offer.token_mint = consignment.token_mint;
-        offer.token_decimals = registry.decimals;
-        offer.id = offer_id;
-        offer.beneficiary = beneficiary_key;
-        offer.token_amount = token_amount;
-        offer.discount_bps = discount_bps;
-        offer.created_at = now;
-        offer.unlock_time = now.checked_add(lockup_secs).ok_or(OtcError::Overflow)?;
-        offer.price_usd_per_token_8d = price_8d;
-        offer.max_price_deviation_bps = consignment.max_price_volatility_bps;
-        offer.sol_usd_price_8d = if currency == 0 { desk.sol_usd_price_8d } else { 0 };
-        offer.currency = currency;
-        offer.approved = auto_approved;
-        offer.paid = false;
-        offer.fulfilled = false;
-        offer.cancelled = false;
-        offer.payer = Pubkey::default();
-        offer.amount_paid = 0;
-        offer.agent_commission_bps = effective_commission_bps;
-
-        emit!(OfferCreated {
-            desk: offer.desk,
-            offer: offer_key,
-            beneficiary: beneficiary_key,
-            token_amount,
-            discount_bps,
-            currency
-        });
-        
-        // Emit approval event for non-negotiable (P2P) offers
-        if auto_approved {
-            emit!(OfferApproved { offer: offer_key, approver: beneficiary_key });
-        }
-        
-        Ok(())
-    }
+        let accounts = &mut *ctx.accounts;
+        open_consignment_offer(
+            &mut accounts.desk,
+            &mut accounts.consignment,
+            &accounts.token_registry,
+            &accounts.payment_registry,
+            &None,
+            &mut accounts.offer,
+            beneficiary,
+            &quote.terms,
+            true,
+        )
+    }
 
     pub fn withdraw_consignment(ctx: Context<WithdrawConsignment>, _consignment_id: u64) -> Result<()> {
         let consignment = &mut ctx.accounts.consignment;
         require!(consignment.consigner == ctx.accounts.consigner.key(), OtcError::NotOwner);
-        require!(consignment.is_active, OtcError::BadState);
+        // Inactive consignments may still hold tokens returned by refunded offers
         let withdraw_amount = consignment.remaining_amount;
         require!(withdraw_amount > 0, OtcError::AmountRange);
 
         consignment.is_active = false;
+        consignment.withdrawn = true;
         consignment.remaining_amount = 0;
+        let registry = &mut ctx.accounts.token_registry;
+        registry.consigned_amount = registry.consigned_amount.checked_sub(withdraw_amount).ok_or(OtcError::Overflow)?;
 
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.desk_token_treasury.to_account_info(),
             to: ctx.accounts.consigner_token_ata.to_account_info(),
-            authority: ctx.accounts.desk_signer.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
             mint: ctx.accounts.token_mint.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
         transfer_checked(cpi_ctx, withdraw_amount, ctx.accounts.token_mint.decimals)?;
         Ok(())
     }
 
+    /// Return an expired consignment's remaining tokens to the consigner (permissionless crank)
+    pub fn expire_consignment(ctx: Context<ExpireConsignment>, _consignment_id: u64) -> Result<()> {
+        let consignment_key = ctx.accounts.consignment.key();
+        let consignment = &mut ctx.accounts.consignment;
+        require!(consignment.is_active, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        require!(consignment_expired(consignment, now)?, OtcError::NotExpired);
+        let returned_amount = consignment.remaining_amount;
+
+        consignment.is_active = false;
+        consignment.remaining_amount = 0;
+        let registry = &mut ctx.accounts.token_registry;
+        registry.consigned_amount = registry.consigned_amount.checked_sub(returned_amount).ok_or(OtcError::Overflow)?;
+
+        if returned_amount > 0 {
+            let desk_key = ctx.accounts.desk.key();
+            let bump = [ctx.accounts.desk.treasury_bump];
+            let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.desk_token_treasury.to_account_info(),
+                to: ctx.accounts.consigner_token_ata.to_account_info(),
+                authority: ctx.accounts.treasury_authority.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            transfer_checked(cpi_ctx, returned_amount, ctx.accounts.token_mint.decimals)?;
+        }
+        emit!(ConsignmentExpired { consignment: consignment_key, consigner: consignment.consigner, returned_amount });
+        Ok(())
+    }
+
+    /// Put a sealed-bid consignment's remaining block up for a commit-reveal auction
+    /// Bids are committed during the bid window with a desk.usdc_mint deposit and revealed afterwards
+    pub fn open_sealed_bid_auction(
+        ctx: Context<OpenSealedBidAuction>,
+        _consignment_id: u64,
+        bid_window_secs: i64,
+        reveal_window_secs: i64,
+        deposit_amount: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.desk.paused, OtcError::Paused);
+        let consignment_key = ctx.accounts.consignment.key();
+        let auction_key = ctx.accounts.auction.key();
+        let consignment = &mut ctx.accounts.consignment;
+        require!(consignment.is_sealed_bid && consignment.is_active && consignment.remaining_amount > 0, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        require!(!consignment_expired(consignment, now)?, OtcError::Expired);
+        require!(bid_window_secs > 0 && reveal_window_secs > 0, OtcError::AmountRange);
+        require!(deposit_amount > 0, OtcError::AmountRange);
+
+        // The whole remaining block moves into the auction so nothing else can sell or withdraw it
+        let token_amount = consignment.remaining_amount;
+        consignment.remaining_amount = 0;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.consignment = consignment_key;
+        auction.token_amount = token_amount;
+        auction.bid_end = now.checked_add(bid_window_secs).ok_or(OtcError::Overflow)?;
+        auction.reveal_end = auction.bid_end.checked_add(reveal_window_secs).ok_or(OtcError::Overflow)?;
+        auction.deposit_amount = deposit_amount;
+        auction.commit_count = 0;
+        auction.released_count = 0;
+        auction.awarded_amount = 0;
+        auction.finished = false;
+        auction.revealed = Vec::new();
+        emit!(SealedBidAuctionOpened { consignment: consignment_key, auction: auction_key, token_amount, bid_end: auction.bid_end, reveal_end: auction.reveal_end, deposit_amount });
+        Ok(())
+    }
+
+    /// Commit to a hidden bid; commitment = sha256(discount_bps, token_amount, salt, bidder) with little-endian integers
+    pub fn commit_sealed_bid(ctx: Context<CommitSealedBid>, _consignment_id: u64, commitment: [u8; 32]) -> Result<()> {
+        let desk = &ctx.accounts.desk;
+        require!(!desk.paused, OtcError::Paused);
+        let auction_key = ctx.accounts.auction.key();
+        let bidder_key = ctx.accounts.bidder.key();
+        let consignment = &ctx.accounts.consignment;
+        if consignment.is_private {
+            require!(
+                bidder_key == consignment.consigner ||
+                bidder_key == desk.owner ||
+                bidder_key == desk.agent ||
+                is_approver(desk.key(), &bidder_key, &ctx.accounts.approver_account),
+                OtcError::FulfillRestricted
+            );
+        }
+        let auction = &mut ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < auction.bid_end, OtcError::Expired);
+        require!((auction.commit_count as usize) < MAX_AUCTION_BIDS, OtcError::TooManyBids);
+
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        require!(payment_registry.is_active, OtcError::UnsupportedCurrency);
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.bidder_usdc_ata.to_account_info(),
+            to: ctx.accounts.desk_payment_treasury.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, auction.deposit_amount, payment_registry.decimals)?;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_add(auction.deposit_amount).ok_or(OtcError::Overflow)?;
+
+        let bid = &mut ctx.accounts.sealed_bid;
+        bid.auction = auction_key;
+        bid.bidder = bidder_key;
+        bid.commitment = commitment;
+        bid.deposit = auction.deposit_amount;
+        bid.revealed = false;
+        auction.commit_count = auction.commit_count.checked_add(1).ok_or(OtcError::Overflow)?;
+        emit!(SealedBidCommitted { auction: auction_key, bidder: bidder_key });
+        Ok(())
+    }
+
+    /// Reveal a committed bid during the reveal window; unrevealed bids forfeit their deposit
+    pub fn reveal_sealed_bid(ctx: Context<RevealSealedBid>, _consignment_id: u64, discount_bps: u16, token_amount: u64, salt: [u8; 32]) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let bidder_key = ctx.accounts.bidder.key();
+        let auction = &mut ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= auction.bid_end, OtcError::NotExpired);
+        require!(now < auction.reveal_end, OtcError::Expired);
+
+        let bid = &mut ctx.accounts.sealed_bid;
+        require!(!bid.revealed, OtcError::BadState);
+        require!(sealed_bid_commitment(discount_bps, token_amount, &salt, &bidder_key) == bid.commitment, OtcError::InvalidBidReveal);
+
+        // The consignment's discount range is the seller's reserve; deal bounds apply per bid
+        let consignment = &ctx.accounts.consignment;
+        require!(discount_bps >= consignment.min_discount_bps && discount_bps <= consignment.max_discount_bps, OtcError::Discount);
+        require!(token_amount >= consignment.min_deal_amount && token_amount <= consignment.max_deal_amount, OtcError::AmountRange);
+        require!(token_amount <= auction.token_amount, OtcError::InsuffInv);
+        if !consignment.is_fractionalized {
+            require!(token_amount == auction.token_amount, OtcError::AmountRange);
+        }
+
+        bid.revealed = true;
+        auction.revealed.push(RevealedBid { bidder: bidder_key, discount_bps, token_amount });
+        emit!(SealedBidRevealed { auction: auction_key, bidder: bidder_key, discount_bps, token_amount });
+        Ok(())
+    }
+
+    /// Award a winning bid after the reveal window: creates its approved offer and holds the deposit
+    /// toward its payment, returning any excess; an award left unpaid forfeits the held deposit
+    /// Bids rank by lowest discount, ties by reveal order; the marginal bid may receive a partial award
+    pub fn settle_sealed_bid(ctx: Context<SettleSealedBid>, _consignment_id: u64) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let auction_key = ctx.accounts.auction.key();
+        let offer_key = ctx.accounts.offer.key();
+        let now = Clock::get()?.unix_timestamp;
+        let desk = &mut ctx.accounts.desk;
+        require!(!desk.paused, OtcError::Paused);
+        let auction = &mut ctx.accounts.auction;
+        require!(now >= auction.reveal_end, OtcError::NotExpired);
+        let bid = &ctx.accounts.sealed_bid;
+        let consignment = &ctx.accounts.consignment;
+        let (award, discount_bps) = sealed_bid_award(auction, consignment, &bid.bidder)?;
+        require!(award > 0, OtcError::BadState);
+
+        let registry = &ctx.accounts.token_registry;
+        require!(registry.token_usd_price_8d > 0, OtcError::NoPrice);
+        if registry.prices_updated_at > 0 {
+            require!(now - registry.prices_updated_at <= desk.max_price_age_secs, OtcError::StalePrice);
+        }
+        let payment_mint = desk.usdc_mint;
+        let payment_usd_price_8d = payment_price_for_offer(desk, desk_key, payment_mint, &Some(ctx.accounts.payment_registry.clone()), now)?;
+        let usd_8d = calc_discounted_usd(award, registry.token_usd_price_8d, registry.decimals, discount_bps)?;
+        let payment_amount = safe_u128_to_u64(mul_div_ceil_u128(usd_8d as u128, pow10(ctx.accounts.payment_registry.decimals as u32), payment_usd_price_8d as u128)?)?;
+        let held_deposit = bid.deposit.min(payment_amount);
+        let returned_deposit = bid.deposit.checked_sub(held_deposit).ok_or(OtcError::Overflow)?;
+
+        let offer_id = desk.next_offer_id;
+        desk.next_offer_id = offer_id.checked_add(1).ok_or(OtcError::Overflow)?;
+
+        // Awards follow the consignment's P2P terms at the winning discount
+        let offer = &mut ctx.accounts.offer;
+        offer.desk = desk_key;
+        offer.consignment_id = consignment.id;
+        offer.token_mint = consignment.token_mint;
+        offer.token_decimals = registry.decimals;
+        offer.id = offer_id;
+        offer.beneficiary = bid.bidder;
+        offer.token_amount = award;
+        offer.discount_bps = discount_bps;
+        offer.created_at = now;
+        offer.unlock_time = now.checked_add(consignment.fixed_lockup_days as i64 * 86400).ok_or(OtcError::Overflow)?;
+        offer.price_usd_per_token_8d = registry.token_usd_price_8d;
+        offer.max_price_deviation_bps = consignment.max_price_volatility_bps;
+        offer.payment_usd_price_8d = payment_usd_price_8d;
+        offer.payment_mint = payment_mint;
+        offer.approved = true;
+        offer.paid = false;
+        offer.fulfilled = false;
+        offer.cancelled = false;
+        offer.payer = Pubkey::default();
+        offer.amount_paid = 0;
+        offer.agent_commission_bps = desk.p2p_commission_bps;
+        offer.consigner_proceeds = 0;
+        offer.vesting_start = offer.unlock_time;
+        offer.vesting_cliff_secs = consignment.vesting_cliff_days as i64 * 86400;
+        offer.vesting_duration_secs = consignment.vesting_duration_days as i64 * 86400;
+        offer.vesting_period_secs = consignment.vesting_period_days as i64 * 86400;
+        offer.claimed_amount = 0;
+        offer.approval_count = 0;
+        offer.expires_at = offer_expires_at(desk, consignment.max_quote_lifetime_secs, 0, now)?;
+        offer.filled_amount = 0;
+        offer.fill_count = 0;
+        offer.syndicated = false;
+        offer.award_deposit = held_deposit;
+        auction.awarded_amount = auction.awarded_amount.checked_add(award).ok_or(OtcError::Overflow)?;
+
+        release_bid_deposit(
+            desk,
+            &mut ctx.accounts.payment_registry,
+            auction,
+            returned_deposit,
+            &ctx.accounts.desk_payment_treasury,
+            &ctx.accounts.bidder_usdc_ata,
+            &ctx.accounts.usdc_mint,
+            &ctx.accounts.treasury_authority,
+            &ctx.accounts.token_program,
+        )?;
+        emit!(OfferCreated { desk: desk_key, offer: offer_key, beneficiary: bid.bidder, token_amount: award, discount_bps, payment_mint });
+        emit!(OfferApproved { offer: offer_key, approver: consignment.consigner });
+        emit!(SealedBidAwarded { auction: auction_key, bidder: bid.bidder, offer: offer_key, token_amount: award, discount_bps });
+        emit!(SealedBidDepositReleased { auction: auction_key, bidder: bid.bidder, amount: returned_deposit, forfeited: false });
+        Ok(())
+    }
+
+    /// Release a losing or unrevealed bid after the reveal window
+    /// Losers get their deposit back; unrevealed deposits are credited to the consigner's proceeds
+    pub fn refund_sealed_bid(ctx: Context<RefundSealedBid>, _consignment_id: u64) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        require!(now >= auction.reveal_end, OtcError::NotExpired);
+        let bid = &ctx.accounts.sealed_bid;
+        let forfeited = !bid.revealed;
+        if forfeited {
+            let consigner_proceeds = ctx.accounts.consigner_proceeds.as_mut().ok_or(OtcError::BadState)?;
+            consigner_proceeds.owed = consigner_proceeds.owed.checked_add(bid.deposit).ok_or(OtcError::Overflow)?;
+            let payment_registry = &mut ctx.accounts.payment_registry;
+            payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(bid.deposit).ok_or(OtcError::Overflow)?;
+            payment_registry.consigner_owed = payment_registry.consigner_owed.checked_add(bid.deposit).ok_or(OtcError::Overflow)?;
+            auction.released_count = auction.released_count.checked_add(1).ok_or(OtcError::Overflow)?;
+        } else {
+            let (award, _) = sealed_bid_award(auction, &ctx.accounts.consignment, &bid.bidder)?;
+            require!(award == 0, OtcError::BadState); // Winners go through settle_sealed_bid
+            release_bid_deposit(
+                &ctx.accounts.desk,
+                &mut ctx.accounts.payment_registry,
+                auction,
+                bid.deposit,
+                &ctx.accounts.desk_payment_treasury,
+                &ctx.accounts.bidder_usdc_ata,
+                &ctx.accounts.usdc_mint,
+                &ctx.accounts.treasury_authority,
+                &ctx.accounts.token_program,
+            )?;
+        }
+        emit!(SealedBidDepositReleased { auction: auction_key, bidder: bid.bidder, amount: bid.deposit, forfeited });
+        Ok(())
+    }
+
+    /// Return the unawarded part of the block to the consignment once every bid has been released
+    /// and close the auction, refunding its rent to the consigner so the consignment can be auctioned again
+    /// An expired consignment stays inactive; the consigner picks the tokens up with withdraw_consignment
+    pub fn finish_sealed_bid_auction(ctx: Context<FinishSealedBidAuction>, _consignment_id: u64) -> Result<()> {
+        let consignment_key = ctx.accounts.consignment.key();
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &mut ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= auction.reveal_end, OtcError::NotExpired);
+        require!(!auction.finished && auction.released_count == auction.commit_count, OtcError::BadState);
+        let returned_amount = auction.token_amount.checked_sub(auction.awarded_amount).ok_or(OtcError::Overflow)?;
+        auction.finished = true;
+        let consignment = &mut ctx.accounts.consignment;
+        consignment.remaining_amount = consignment.remaining_amount.checked_add(returned_amount).ok_or(OtcError::Overflow)?;
+        emit!(SealedBidAuctionFinished { consignment: consignment_key, auction: auction_key, awarded_amount: auction.awarded_amount, returned_amount });
+        Ok(())
+    }
+
+    /// Close a sealed-bid award left unpaid past its expiry (permissionless crank)
+    /// The held deposit is credited to the consigner's proceeds and the tokens go back to the consignment
+    pub fn forfeit_award_deposit(ctx: Context<ForfeitAwardDeposit>, _offer_id: u64) -> Result<()> {
+        let offer_key = ctx.accounts.offer.key();
+        let offer = &mut ctx.accounts.offer;
+        require!(offer.award_deposit > 0 && !offer.paid && !offer.cancelled, OtcError::BadState);
+        require!(Clock::get()?.unix_timestamp > offer.expires_at, OtcError::NotExpired);
+        let forfeited = offer.award_deposit;
+        offer.award_deposit = 0;
+        let unfilled = close_unfilled_offer(offer)?;
+
+        let consignment = &mut ctx.accounts.consignment;
+        consignment.remaining_amount = consignment.remaining_amount.checked_add(unfilled).ok_or(OtcError::Overflow)?;
+
+        let consigner_proceeds = &mut ctx.accounts.consigner_proceeds;
+        consigner_proceeds.owed = consigner_proceeds.owed.checked_add(forfeited).ok_or(OtcError::Overflow)?;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(forfeited).ok_or(OtcError::Overflow)?;
+        payment_registry.consigner_owed = payment_registry.consigner_owed.checked_add(forfeited).ok_or(OtcError::Overflow)?;
+        emit!(AwardDepositForfeited { offer: offer_key, consigner: consigner_proceeds.consigner, amount: forfeited });
+        Ok(())
+    }
+
+    /// Post a buyer RFQ bid escrowing lamports in the bid account
+    /// max_lockup_secs bounds lockup plus vesting duration of the resulting offer
+    pub fn place_bid_sol(
+        ctx: Context<PlaceBidSol>,
+        bid_id: u64,
+        token_amount: u64,
+        max_discount_bps: u16,
+        max_lockup_secs: i64,
+        escrow_amount: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.desk.paused, OtcError::Paused);
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&ctx.accounts.bidder.key(), &ctx.accounts.bid.key(), escrow_amount);
+        anchor_lang::solana_program::program::invoke(&ix, &[
+            ctx.accounts.bidder.to_account_info(),
+            ctx.accounts.bid.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ])?;
+        let bid_key = ctx.accounts.bid.key();
+        init_bid(&mut ctx.accounts.bid, ctx.accounts.desk.key(), ctx.accounts.bidder.key(), bid_id, &ctx.accounts.token_registry, NATIVE_SOL, token_amount, max_discount_bps, max_lockup_secs, escrow_amount)?;
+        emit_bid_placed(&ctx.accounts.bid, bid_key);
+        Ok(())
+    }
+
+    /// Post a buyer RFQ bid escrowing a registered SPL payment mint in the desk treasury
+    pub fn place_bid_usdc(
+        ctx: Context<PlaceBidUsdc>,
+        bid_id: u64,
+        token_amount: u64,
+        max_discount_bps: u16,
+        max_lockup_secs: i64,
+        escrow_amount: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.desk.paused, OtcError::Paused);
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        require!(payment_registry.is_active, OtcError::UnsupportedCurrency);
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.bidder_payment_ata.to_account_info(),
+            to: ctx.accounts.desk_payment_treasury.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, escrow_amount, payment_registry.decimals)?;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_add(escrow_amount).ok_or(OtcError::Overflow)?;
+        let bid_key = ctx.accounts.bid.key();
+        init_bid(&mut ctx.accounts.bid, ctx.accounts.desk.key(), ctx.accounts.bidder.key(), bid_id, &ctx.accounts.token_registry, payment_registry.mint, token_amount, max_discount_bps, max_lockup_secs, escrow_amount)?;
+        emit_bid_placed(&ctx.accounts.bid, bid_key);
+        Ok(())
+    }
+
+    /// Cancel a SOL bid; closing the bid account returns the escrow with its rent
+    pub fn cancel_bid_sol(ctx: Context<CancelBidSol>, _bid_id: u64) -> Result<()> {
+        let bid = &ctx.accounts.bid;
+        require!(bid.payment_mint == NATIVE_SOL, OtcError::BadState);
+        emit!(BidCancelled { bid: bid.key(), bidder: bid.bidder, refunded: bid.escrow_amount });
+        Ok(())
+    }
+
+    /// Cancel an SPL bid and refund its escrow from the desk treasury
+    pub fn cancel_bid_usdc(ctx: Context<CancelBidUsdc>, _bid_id: u64) -> Result<()> {
+        let bid = &ctx.accounts.bid;
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.desk_payment_treasury.to_account_info(),
+            to: ctx.accounts.bidder_payment_ata.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, bid.escrow_amount, ctx.accounts.payment_registry.decimals)?;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(bid.escrow_amount).ok_or(OtcError::Overflow)?;
+        emit!(BidCancelled { bid: bid.key(), bidder: bid.bidder, refunded: bid.escrow_amount });
+        Ok(())
+    }
+
+    /// Sell a consignment's tokens into a SOL bid: creates a paid offer for the bidder in one step
+    /// Unused escrow and the bid account's rent go back to the bidder
+    pub fn hit_bid_sol(ctx: Context<HitBidSol>, _consignment_id: u64, discount_bps: u16, lockup_secs: i64) -> Result<()> {
+        let bid_key = ctx.accounts.bid.key();
+        let offer_key = ctx.accounts.offer.key();
+        let desk_key = ctx.accounts.desk.key();
+        require!(ctx.accounts.bid.payment_mint == NATIVE_SOL, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        let payment_usd_price_8d = payment_price_for_offer(&ctx.accounts.desk, desk_key, NATIVE_SOL, &None, now)?;
+        let (payment_amount, commission) = open_offer_for_bid(
+            &mut ctx.accounts.desk,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.token_registry,
+            &ctx.accounts.bid,
+            &mut ctx.accounts.offer,
+            discount_bps,
+            lockup_secs,
+            payment_usd_price_8d,
+            9,
+            now,
+        )?;
+
+        // Bid and desk are both program-owned, so lamports move directly
+        let bid_ai = ctx.accounts.bid.to_account_info();
+        let desk_ai = ctx.accounts.desk.to_account_info();
+        move_lamports(&bid_ai, &desk_ai, payment_amount)?;
+        if commission > 0 {
+            if let Some(agent_account) = &ctx.accounts.agent {
+                require!(agent_account.key() == ctx.accounts.desk.agent, OtcError::BadState);
+                move_lamports(&desk_ai, &agent_account.to_account_info(), commission)?;
+                emit!(AgentCommissionPaid { offer: offer_key, agent: agent_account.key(), amount: commission, payment_mint: NATIVE_SOL });
+            }
+        }
+
+        let proceeds = ctx.accounts.offer.consigner_proceeds;
+        let consigner_proceeds = &mut ctx.accounts.consigner_proceeds;
+        consigner_proceeds.owed = consigner_proceeds.owed.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+        let desk = &mut ctx.accounts.desk;
+        desk.consigner_sol_owed = desk.consigner_sol_owed.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+
+        let bid = &ctx.accounts.bid;
+        let refunded = bid.escrow_amount.checked_sub(payment_amount).ok_or(OtcError::Overflow)?;
+        emit!(ConsignerProceedsCredited { offer: offer_key, consigner: consigner_proceeds.consigner, amount: proceeds, payment_mint: NATIVE_SOL });
+        emit!(OfferPaid { offer: offer_key, payer: bid.bidder, amount: payment_amount, payment_mint: NATIVE_SOL });
+        emit!(BidHit { bid: bid_key, offer: offer_key, consigner: consigner_proceeds.consigner, token_amount: bid.token_amount, discount_bps, amount: payment_amount, refunded });
+        Ok(())
+    }
+
+    /// Sell a consignment's tokens into an SPL bid: creates a paid offer for the bidder in one step
+    pub fn hit_bid_usdc(ctx: Context<HitBidUsdc>, _consignment_id: u64, discount_bps: u16, lockup_secs: i64) -> Result<()> {
+        let bid_key = ctx.accounts.bid.key();
+        let offer_key = ctx.accounts.offer.key();
+        let desk_key = ctx.accounts.desk.key();
+        let payment_mint = ctx.accounts.bid.payment_mint;
+        require!(payment_mint != NATIVE_SOL, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        let payment_usd_price_8d = payment_price_for_offer(&ctx.accounts.desk, desk_key, payment_mint, &Some(ctx.accounts.payment_registry.clone()), now)?;
+        let payment_decimals = ctx.accounts.payment_registry.decimals;
+        let (payment_amount, commission) = open_offer_for_bid(
+            &mut ctx.accounts.desk,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.token_registry,
+            &ctx.accounts.bid,
+            &mut ctx.accounts.offer,
+            discount_bps,
+            lockup_secs,
+            payment_usd_price_8d,
+            payment_decimals,
+            now,
+        )?;
+
+        let bid = &ctx.accounts.bid;
+        let refunded = bid.escrow_amount.checked_sub(payment_amount).ok_or(OtcError::Overflow)?;
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        // The payment is already in the treasury; pay out commission and unused escrow
+        if commission > 0 {
+            if let Some(agent_payment_ata) = &ctx.accounts.agent_payment_ata {
+                require!(agent_payment_ata.owner == ctx.accounts.desk.agent, OtcError::BadState);
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.desk_payment_treasury.to_account_info(),
+                    to: agent_payment_ata.to_account_info(),
+                    authority: ctx.accounts.treasury_authority.to_account_info(),
+                    mint: ctx.accounts.payment_mint.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+                transfer_checked(cpi_ctx, commission, payment_decimals)?;
+                emit!(AgentCommissionPaid { offer: offer_key, agent: ctx.accounts.desk.agent, amount: commission, payment_mint });
+            }
+        }
+        if refunded > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.desk_payment_treasury.to_account_info(),
+                to: ctx.accounts.bidder_payment_ata.to_account_info(),
+                authority: ctx.accounts.treasury_authority.to_account_info(),
+                mint: ctx.accounts.payment_mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            transfer_checked(cpi_ctx, refunded, payment_decimals)?;
+        }
+
+        let proceeds = ctx.accounts.offer.consigner_proceeds;
+        let consigner_proceeds = &mut ctx.accounts.consigner_proceeds;
+        consigner_proceeds.owed = consigner_proceeds.owed.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(bid.escrow_amount).ok_or(OtcError::Overflow)?;
+        payment_registry.consigner_owed = payment_registry.consigner_owed.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+
+        emit!(ConsignerProceedsCredited { offer: offer_key, consigner: consigner_proceeds.consigner, amount: proceeds, payment_mint });
+        emit!(OfferPaid { offer: offer_key, payer: bid.bidder, amount: payment_amount, payment_mint });
+        emit!(BidHit { bid: bid_key, offer: offer_key, consigner: consigner_proceeds.consigner, token_amount: bid.token_amount, discount_bps, amount: payment_amount, refunded });
+        Ok(())
+    }
+
     pub fn approve_offer(ctx: Context<ApproveOffer>, _offer_id: u64) -> Result<()> {
         let desk = &ctx.accounts.desk;
         require!(!desk.paused, OtcError::Paused);
-        must_be_approver(desk, &ctx.accounts.approver.key())?;
         
         let offer_key = ctx.accounts.offer.key();
         let approver_key = ctx.accounts.approver.key();
@@ -816,9 +1712,39 @@ pub mod otc {
         // Non-negotiable offers are P2P (auto-approved at creation) - cannot be manually approved
         let consignment = &ctx.accounts.consignment;
         require!(consignment.is_negotiable, OtcError::NonNegotiableP2P);
-        
-        offer.approved = true;
-        emit!(OfferApproved { offer: offer_key, approver: approver_key });
+
+        let usd_8d = calc_discounted_usd(offer.token_amount, offer.price_usd_per_token_8d, offer.token_decimals, offer.discount_bps)?;
+        // The desk agent approves without limits; everyone else spends from their Approver budget
+        if approver_key != desk.agent {
+            let record = ctx.accounts.approver_account.as_mut().ok_or(OtcError::NotApprover)?;
+            require!(record.desk == desk.key() && record.approver == approver_key, OtcError::NotApprover);
+            require!(usd_8d <= record.max_usd_per_offer_8d, OtcError::ApprovalLimitExceeded);
+            if desk.senior_approval_usd_8d > 0 && usd_8d >= desk.senior_approval_usd_8d {
+                require!(record.is_senior, OtcError::SeniorApprovalRequired);
+            }
+            let day = Clock::get()?.unix_timestamp / 86400;
+            if record.day_index != day {
+                record.day_index = day;
+                record.day_used_usd_8d = 0;
+            }
+            let used = record.day_used_usd_8d.checked_add(usd_8d).ok_or(OtcError::Overflow)?;
+            require!(used <= record.daily_limit_usd_8d, OtcError::ApprovalLimitExceeded);
+            record.day_used_usd_8d = used;
+        }
+
+        // The approval PDA can only be created once per approver, so nobody approves twice
+        let approval = &mut ctx.accounts.offer_approval;
+        approval.offer = offer_key;
+        approval.approver = approver_key;
+        approval.approved_at = Clock::get()?.unix_timestamp;
+
+        offer.approval_count = offer.approval_count.checked_add(1).ok_or(OtcError::Overflow)?;
+        let required = required_approvals_for(desk, usd_8d);
+        emit!(OfferApprovalRecorded { offer: offer_key, approver: approver_key, approvals: offer.approval_count, required });
+        if offer.approval_count >= required {
+            offer.approved = true;
+            emit!(OfferApproved { offer: offer_key, approver: approver_key });
+        }
         Ok(())
     }
 
@@ -831,21 +1757,25 @@ pub mod otc {
         let now = Clock::get()?.unix_timestamp;
         
         let offer = &mut ctx.accounts.offer;
-        require!(!offer.paid && !offer.fulfilled, OtcError::BadState);
+        require!(!offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
+        // Consignment offers must go through cancel_offer_with_consignment to restore inventory
+        require!(offer.consignment_id == 0, OtcError::BadState);
         
+        // Expired quotes can be cancelled by anyone so their reservation is released
+        let expired = now >= offer.expires_at;
         if caller == offer.beneficiary {
-            let expiry = offer.created_at.checked_add(desk.quote_expiry_secs).ok_or(OtcError::Overflow)?;
-            require!(now >= expiry, OtcError::NotExpired);
-        } else if caller == desk.owner || caller == desk.agent || desk.approvers.contains(&caller) {
+            require!(expired, OtcError::NotExpired);
+        } else if expired || caller == desk.owner || caller == desk.agent || is_approver(desk.key(), &caller, &ctx.accounts.approver_account) {
         } else {
             return err!(OtcError::NotApprover);
         }
         
-        offer.cancelled = true;
+        let unfilled = close_unfilled_offer(offer)?;
         
-        // Restore tokens to consignment if this offer was from one
-        // Note: consignment account must be passed via remaining_accounts if needed
-        // For now, this is handled in CancelOfferWithConsignment instruction
+        // Release the unfilled inventory reserved at creation back to the desk
+        let registry = &mut ctx.accounts.token_registry;
+        registry.reserved_amount = registry.reserved_amount.checked_sub(unfilled).ok_or(OtcError::Overflow)?;
+        registry.desk_owned_amount = registry.desk_owned_amount.checked_add(unfilled).ok_or(OtcError::Overflow)?;
         
         emit!(OfferCancelled { offer: offer_key, by: caller });
         Ok(())
@@ -863,22 +1793,22 @@ pub mod otc {
         let offer = &mut ctx.accounts.offer;
         require!(!offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
         require!(offer.consignment_id > 0, OtcError::BadState); // Must be from consignment
+        require!(offer.award_deposit == 0, OtcError::BadState); // Unpaid sealed-bid awards close through forfeit_award_deposit
         
         if caller == offer.beneficiary {
-            let expiry = offer.created_at.checked_add(desk.quote_expiry_secs).ok_or(OtcError::Overflow)?;
-            require!(now >= expiry, OtcError::NotExpired);
-        } else if caller == desk.owner || caller == desk.agent || desk.approvers.contains(&caller) {
+            require!(now >= offer.expires_at, OtcError::NotExpired);
+        } else if caller == desk.owner || caller == desk.agent || is_approver(desk.key(), &caller, &ctx.accounts.approver_account) {
         } else {
             return err!(OtcError::NotApprover);
         }
         
-        let token_amount = offer.token_amount;
-        offer.cancelled = true;
+        let unfilled = close_unfilled_offer(offer)?;
         
-        // Restore tokens to consignment
+        // Restore unfilled tokens to consignment
         let consignment = &mut ctx.accounts.consignment;
-        consignment.remaining_amount = consignment.remaining_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
-        if !consignment.is_active {
+        restore_consignment_inventory(offer, unfilled, desk.key(), Some(&mut **consignment))?;
+        // A consignment the offer sold out goes back on sale; one withdrawn or expired stays closed
+        if !consignment.withdrawn && !consignment_expired(consignment, now)? {
             consignment.is_active = true;
         }
         
@@ -886,293 +1816,613 @@ pub mod otc {
         Ok(())
     }
 
-    pub fn fulfill_offer_usdc(ctx: Context<FulfillOfferUsdc>, _offer_id: u64) -> Result<()> {
+    /// Pay for an offer with its SPL payment mint (USDC, USDT, PYUSD, wSOL, ...)
+    /// max_payment_amount: upper bound on payment mint units pulled from the payer
+    pub fn fulfill_offer_usdc(ctx: Context<FulfillOfferUsdc>, _offer_id: u64, max_payment_amount: u64) -> Result<()> {
         // Cache keys before mutable borrows to avoid borrow checker issues
         let offer_key = ctx.accounts.offer.key();
         let payer_key = ctx.accounts.payer.key();
+        let desk_key = ctx.accounts.desk.key();
         
-        let desk = &mut ctx.accounts.desk;
+        let desk = &ctx.accounts.desk;
         require!(!desk.paused, OtcError::Paused);
-        // Removed PDA validation - now using keypairs for offers
         let offer = &mut ctx.accounts.offer;
-        require!(offer.currency == 1, OtcError::BadState);
-        require!(offer.approved, OtcError::NotApproved);
-        require!(!offer.cancelled && !offer.paid && !offer.fulfilled, OtcError::BadState);
+        require!(offer.payment_mint != NATIVE_SOL, OtcError::BadState);
         let now = Clock::get()?.unix_timestamp;
-        let expiry = offer.created_at.checked_add(desk.quote_expiry_secs).ok_or(OtcError::Overflow)?;
-        require!(now <= expiry, OtcError::Expired);
-        require!(ctx.accounts.desk_token_treasury.amount >= offer.token_amount, OtcError::InsuffInv);
-        if desk.restrict_fulfill {
-            let caller = ctx.accounts.payer.key();
-            require!(caller == offer.beneficiary || caller == desk.owner || caller == desk.agent || desk.approvers.contains(&caller), OtcError::FulfillRestricted);
+        check_fulfill(offer, now)?;
+        begin_offer_payment(desk, offer, &mut ctx.accounts.token_registry, payer_key, &ctx.accounts.approver_account, offer.token_amount, now)?;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        require!(payment_registry.is_active, OtcError::UnsupportedCurrency);
+        let (payment_amount, commission_amount) = offer_payment(desk, offer, offer.token_amount, payment_registry.decimals)?;
+        // A sealed-bid award's held deposit counts toward its payment; only the winning bidder can pay it off
+        if offer.award_deposit > 0 {
+            require!(payer_key == offer.beneficiary, OtcError::FulfillRestricted);
         }
-        let usd_8d = calc_discounted_usd(offer.token_amount, offer.price_usd_per_token_8d, offer.token_decimals, offer.discount_bps)?;
-        let usdc_amount = safe_u128_to_u64(mul_div_ceil_u128(usd_8d as u128, 1_000_000u128, 100_000_000u128)?)?;
+        let payer_amount = payment_amount.checked_sub(offer.award_deposit).ok_or(OtcError::Overflow)?;
+        require!(payer_amount <= max_payment_amount, OtcError::SlippageExceeded);
         
-        // Calculate agent commission (from seller proceeds)
-        let commission_usd_8d = usd_8d.checked_mul(offer.agent_commission_bps as u64).ok_or(OtcError::Overflow)?.checked_div(10_000).ok_or(OtcError::Overflow)?;
-        let commission_usdc = safe_u128_to_u64(mul_div_u128(commission_usd_8d as u128, 1_000_000u128, 100_000_000u128)?)?;
-        
-        // Transfer full payment from buyer to desk treasury
-        let cpi_accounts = TransferChecked { 
-            from: ctx.accounts.payer_usdc_ata.to_account_info(), 
-            to: ctx.accounts.desk_usdc_treasury.to_account_info(), 
-            authority: ctx.accounts.payer.to_account_info(),
-            mint: ctx.accounts.usdc_mint.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        transfer_checked(cpi_ctx, usdc_amount, desk.usdc_decimals)?;
-        
-        // If there's a commission and agent USDC account is provided, transfer commission to agent
-        // SECURITY: Validate agent_usdc_ata owner matches desk.agent to prevent commission theft
-        if commission_usdc > 0 {
-            if let Some(agent_usdc_ata) = &ctx.accounts.agent_usdc_ata {
-                require!(agent_usdc_ata.owner == desk.agent, OtcError::BadState);
-                // Transfer commission from desk treasury to agent (desk_signer authorizes)
-                let cpi_accounts_commission = TransferChecked { 
-                    from: ctx.accounts.desk_usdc_treasury.to_account_info(), 
-                    to: agent_usdc_ata.to_account_info(), 
-                    authority: ctx.accounts.desk_signer.to_account_info(),
-                    mint: ctx.accounts.usdc_mint.to_account_info(),
-                };
-                let cpi_ctx_commission = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts_commission);
-                transfer_checked(cpi_ctx_commission, commission_usdc, desk.usdc_decimals)?;
-                emit!(AgentCommissionPaid { offer: offer_key, agent: desk.agent, amount: commission_usdc, currency: 1 });
-            }
-        }
+        // Transfer the payment, less any held deposit, from buyer to desk treasury
+        collect_spl_payment(
+            desk,
+            offer,
+            payer_amount,
+            commission_amount,
+            payment_registry.decimals,
+            &ctx.accounts.payer,
+            &ctx.accounts.payer_payment_ata,
+            &ctx.accounts.desk_payment_treasury,
+            &ctx.accounts.agent_payment_ata,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.treasury_authority,
+            &ctx.accounts.token_program,
+        )?;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(offer.award_deposit).ok_or(OtcError::Overflow)?;
         
-        offer.amount_paid = usdc_amount; offer.payer = payer_key; offer.paid = true;
+        offer.consigner_proceeds = credit_consigner_proceeds(
+            offer,
+            desk_key,
+            payment_amount,
+            commission_amount,
+            &ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut payment_registry.consigner_owed,
+        )?;
+        offer.amount_paid = payment_amount; offer.payer = payer_key; offer.paid = true;
+        offer.filled_amount = offer.token_amount;
         // Note: desk.token_reserved is deprecated since all tokens are equal now
-        emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: usdc_amount, currency: 1 });
+        emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: payment_amount, payment_mint: offer.payment_mint });
         Ok(())
     }
 
-    pub fn fulfill_offer_sol(ctx: Context<FulfillOfferSol>, _offer_id: u64) -> Result<()> {
+    /// Pay for an offer in native SOL
+    /// max_payment_amount: upper bound on lamports pulled from the payer
+    pub fn fulfill_offer_sol(ctx: Context<FulfillOfferSol>, _offer_id: u64, max_payment_amount: u64) -> Result<()> {
         // Cache keys before mutable borrows to avoid borrow checker issues
         let offer_key = ctx.accounts.offer.key();
         let payer_key = ctx.accounts.payer.key();
+        let desk_key = ctx.accounts.desk.key();
         
-        let desk_ai = ctx.accounts.desk.to_account_info();
-        let desk_key = desk_ai.key();
         let desk = &mut ctx.accounts.desk;
-        let agent_key = desk.agent;
         require!(!desk.paused, OtcError::Paused);
-        // Removed PDA validation - now using keypairs for offers
         let offer = &mut ctx.accounts.offer;
-        require!(offer.currency == 0, OtcError::BadState);
-        require!(offer.approved, OtcError::NotApproved);
-        require!(!offer.cancelled && !offer.paid && !offer.fulfilled, OtcError::BadState);
+        require!(offer.payment_mint == NATIVE_SOL, OtcError::BadState);
         let now = Clock::get()?.unix_timestamp;
-        let expiry = offer.created_at.checked_add(desk.quote_expiry_secs).ok_or(OtcError::Overflow)?;
-        require!(now <= expiry, OtcError::Expired);
-        require!(ctx.accounts.desk_token_treasury.amount >= offer.token_amount, OtcError::InsuffInv);
-        if desk.restrict_fulfill {
-            let caller = ctx.accounts.payer.key();
-            require!(caller == offer.beneficiary || caller == desk.owner || caller == desk.agent || desk.approvers.contains(&caller), OtcError::FulfillRestricted);
-        }
-        let usd_8d = calc_discounted_usd(offer.token_amount, offer.price_usd_per_token_8d, offer.token_decimals, offer.discount_bps)?;
-        let sol_usd = if offer.sol_usd_price_8d > 0 { offer.sol_usd_price_8d } else { desk.sol_usd_price_8d };
-        require!(sol_usd > 0, OtcError::NoPrice);
-        let lamports_req = safe_u128_to_u64(mul_div_ceil_u128(usd_8d as u128, 1_000_000_000u128, sol_usd as u128)?)?;
-        
-        // Calculate agent commission (from seller proceeds)
-        let commission_usd_8d = usd_8d.checked_mul(offer.agent_commission_bps as u64).ok_or(OtcError::Overflow)?.checked_div(10_000).ok_or(OtcError::Overflow)?;
-        let commission_lamports = safe_u128_to_u64(mul_div_u128(commission_usd_8d as u128, 1_000_000_000u128, sol_usd as u128)?)?;
+        check_fulfill(offer, now)?;
+        begin_offer_payment(desk, offer, &mut ctx.accounts.token_registry, payer_key, &ctx.accounts.approver_account, offer.token_amount, now)?;
+        let (lamports_req, commission_lamports) = offer_payment(desk, offer, offer.token_amount, 9)?;
+        require!(lamports_req <= max_payment_amount, OtcError::SlippageExceeded);
         
         // Transfer full payment from buyer to desk
-        let ix = anchor_lang::solana_program::system_instruction::transfer(&ctx.accounts.payer.key(), &desk_key, lamports_req);
-        anchor_lang::solana_program::program::invoke(&ix, &[
-            ctx.accounts.payer.to_account_info(),
-            desk_ai.clone(),
-            ctx.accounts.system_program.to_account_info(),
-        ])?;
-        
-        // If there's a commission and agent account is provided, transfer commission to agent
-        // SECURITY: Validate agent account matches desk.agent to prevent commission theft
-        if commission_lamports > 0 {
-            if let Some(agent_account) = &ctx.accounts.agent {
-                require!(agent_account.key() == agent_key, OtcError::BadState);
-                // Transfer commission from desk to agent (desk_signer authorizes)
-                **desk_ai.try_borrow_mut_lamports()? -= commission_lamports;
-                **agent_account.to_account_info().try_borrow_mut_lamports()? += commission_lamports;
-                emit!(AgentCommissionPaid { offer: offer_key, agent: agent_key, amount: commission_lamports, currency: 0 });
-            }
-        }
+        collect_sol_payment(desk, offer, lamports_req, commission_lamports, &ctx.accounts.payer, &ctx.accounts.agent, &ctx.accounts.system_program)?;
         
+        offer.consigner_proceeds = credit_consigner_proceeds(
+            offer,
+            desk_key,
+            lamports_req,
+            commission_lamports,
+            &ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut desk.consigner_sol_owed,
+        )?;
         offer.amount_paid = lamports_req; offer.payer = payer_key; offer.paid = true;
+        offer.filled_amount = offer.token_amount;
         // Note: desk.token_reserved is deprecated since all tokens are equal now
-        emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: lamports_req, currency: 0 });
+        emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: lamports_req, payment_mint: NATIVE_SOL });
+        Ok(())
+    }
+
+    /// Let other payers fill tranches of the beneficiary's offer; only before the first payment
+    pub fn set_offer_syndicated(ctx: Context<SetOfferSyndicated>, _offer_id: u64, syndicated: bool) -> Result<()> {
+        let offer = &mut ctx.accounts.offer;
+        require!(!offer.cancelled && !offer.paid && offer.filled_amount == 0, OtcError::BadState);
+        offer.syndicated = syndicated;
+        Ok(())
+    }
+
+    /// Pay for a tranche of an approved offer with its SPL payment mint
+    /// Each payer's tranches accumulate in an OfferFill record and are delivered by claim_fill
+    pub fn fill_offer_usdc(ctx: Context<FillOfferUsdc>, _offer_id: u64, token_amount: u64, max_payment_amount: u64) -> Result<()> {
+        let offer_key = ctx.accounts.offer.key();
+        let payer_key = ctx.accounts.payer.key();
+        let desk_key = ctx.accounts.desk.key();
+        
+        let desk = &ctx.accounts.desk;
+        require!(!desk.paused, OtcError::Paused);
+        let offer = &mut ctx.accounts.offer;
+        require!(offer.payment_mint != NATIVE_SOL, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        check_fill(desk, offer, &payer_key, token_amount, now)?;
+        begin_offer_payment(desk, offer, &mut ctx.accounts.token_registry, payer_key, &ctx.accounts.approver_account, token_amount, now)?;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        require!(payment_registry.is_active, OtcError::UnsupportedCurrency);
+        // Agent commission is taken from each tranche's seller proceeds
+        let (payment_amount, commission_amount) = offer_payment(desk, offer, token_amount, payment_registry.decimals)?;
+        require!(payment_amount <= max_payment_amount, OtcError::SlippageExceeded);
+        
+        collect_spl_payment(
+            desk,
+            offer,
+            payment_amount,
+            commission_amount,
+            payment_registry.decimals,
+            &ctx.accounts.payer,
+            &ctx.accounts.payer_payment_ata,
+            &ctx.accounts.desk_payment_treasury,
+            &ctx.accounts.agent_payment_ata,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.treasury_authority,
+            &ctx.accounts.token_program,
+        )?;
+        
+        let proceeds = credit_consigner_proceeds(
+            offer,
+            desk_key,
+            payment_amount,
+            commission_amount,
+            &ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut payment_registry.consigner_owed,
+        )?;
+        record_fill(offer, &mut ctx.accounts.offer_fill, offer_key, payer_key, token_amount, payment_amount, proceeds)?;
+        emit!(OfferFilled { offer: offer_key, payer: payer_key, token_amount, amount: payment_amount, payment_mint: offer.payment_mint, filled_amount: offer.filled_amount });
+        if offer.paid {
+            emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: offer.amount_paid, payment_mint: offer.payment_mint });
+        }
+        Ok(())
+    }
+
+    /// Pay for a tranche of an approved offer in native SOL
+    pub fn fill_offer_sol(ctx: Context<FillOfferSol>, _offer_id: u64, token_amount: u64, max_payment_amount: u64) -> Result<()> {
+        let offer_key = ctx.accounts.offer.key();
+        let payer_key = ctx.accounts.payer.key();
+        let desk_key = ctx.accounts.desk.key();
+        
+        let desk = &mut ctx.accounts.desk;
+        require!(!desk.paused, OtcError::Paused);
+        let offer = &mut ctx.accounts.offer;
+        require!(offer.payment_mint == NATIVE_SOL, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        check_fill(desk, offer, &payer_key, token_amount, now)?;
+        begin_offer_payment(desk, offer, &mut ctx.accounts.token_registry, payer_key, &ctx.accounts.approver_account, token_amount, now)?;
+        let (lamports_req, commission_lamports) = offer_payment(desk, offer, token_amount, 9)?;
+        require!(lamports_req <= max_payment_amount, OtcError::SlippageExceeded);
+        
+        collect_sol_payment(desk, offer, lamports_req, commission_lamports, &ctx.accounts.payer, &ctx.accounts.agent, &ctx.accounts.system_program)?;
+        
+        let proceeds = credit_consigner_proceeds(
+            offer,
+            desk_key,
+            lamports_req,
+            commission_lamports,
+            &ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut desk.consigner_sol_owed,
+        )?;
+        record_fill(offer, &mut ctx.accounts.offer_fill, offer_key, payer_key, token_amount, lamports_req, proceeds)?;
+        emit!(OfferFilled { offer: offer_key, payer: payer_key, token_amount, amount: lamports_req, payment_mint: NATIVE_SOL, filled_amount: offer.filled_amount });
+        if offer.paid {
+            emit!(OfferPaid { offer: offer_key, payer: payer_key, amount: offer.amount_paid, payment_mint: NATIVE_SOL });
+        }
         Ok(())
     }
 
+    /// Pay for an offer with no remaining lockup or vesting and deliver its tokens in the same instruction
+    pub fn fulfill_and_claim_usdc<'info>(ctx: Context<'_, '_, '_, 'info, FulfillAndClaimUsdc<'info>>, offer_id: u64, max_payment_amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(vested_amount(&ctx.accounts.fulfill.offer, now)? == ctx.accounts.fulfill.offer.token_amount, OtcError::Locked);
+        fulfill_offer_usdc(
+            Context::new(ctx.program_id, &mut ctx.accounts.fulfill, ctx.remaining_accounts, ctx.bumps.fulfill),
+            offer_id,
+            max_payment_amount,
+        )?;
+        let accounts = &mut *ctx.accounts;
+        deliver_offer_tokens(
+            &accounts.fulfill.desk,
+            &mut accounts.fulfill.offer,
+            &mut accounts.fulfill.token_registry,
+            &accounts.fulfill.desk_token_treasury,
+            &accounts.beneficiary_token_ata,
+            &accounts.token_mint,
+            &accounts.fulfill.treasury_authority,
+            &accounts.token_program,
+        )
+    }
+
+    /// Pay in native SOL for an offer with no remaining lockup or vesting and deliver its tokens in the same instruction
+    pub fn fulfill_and_claim_sol<'info>(ctx: Context<'_, '_, '_, 'info, FulfillAndClaimSol<'info>>, offer_id: u64, max_payment_amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(vested_amount(&ctx.accounts.fulfill.offer, now)? == ctx.accounts.fulfill.offer.token_amount, OtcError::Locked);
+        fulfill_offer_sol(
+            Context::new(ctx.program_id, &mut ctx.accounts.fulfill, ctx.remaining_accounts, ctx.bumps.fulfill),
+            offer_id,
+            max_payment_amount,
+        )?;
+        let accounts = &mut *ctx.accounts;
+        deliver_offer_tokens(
+            &accounts.fulfill.desk,
+            &mut accounts.fulfill.offer,
+            &mut accounts.fulfill.token_registry,
+            &accounts.fulfill.desk_token_treasury,
+            &accounts.beneficiary_token_ata,
+            &accounts.token_mint,
+            &accounts.fulfill.treasury_authority,
+            &accounts.token_program,
+        )
+    }
+
+    /// Deliver vested tokens to the beneficiary; permissionless since tokens can only go to offer.beneficiary
     pub fn claim(ctx: Context<Claim>, _offer_id: u64) -> Result<()> {
-        // Desk keypair signs to authorize token transfer
         let desk = &ctx.accounts.desk;
         require!(!desk.paused, OtcError::Paused);
-        require!(ctx.accounts.desk_signer.key() == desk.key(), OtcError::NotOwner);
         
         let offer_key = ctx.accounts.offer.key();
         let offer = &mut ctx.accounts.offer;
         require!(ctx.accounts.beneficiary.key() == offer.beneficiary, OtcError::NotOwner);
         require!(offer.paid && !offer.cancelled && !offer.fulfilled, OtcError::BadState);
+        require!(offer.fill_count == 0, OtcError::BadState); // Syndicated offers are claimed per fill
         let now = Clock::get()?.unix_timestamp;
         require!(now >= offer.unlock_time, OtcError::Locked);
+        // Only the vested-but-unclaimed part is released; without vesting this is the whole amount
+        let claimable = vested_amount(offer, now)?.checked_sub(offer.claimed_amount).ok_or(OtcError::Overflow)?;
+        require!(claimable > 0, OtcError::Locked);
         
-        // Transfer tokens from desk treasury to beneficiary (desk_signer authorizes)
+        // Transfer tokens from desk treasury to beneficiary (treasury PDA authorizes)
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.desk_token_treasury.to_account_info(),
             to: ctx.accounts.beneficiary_token_ata.to_account_info(),
-            authority: ctx.accounts.desk_signer.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
             mint: ctx.accounts.token_mint.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        transfer_checked(cpi_ctx, offer.token_amount, offer.token_decimals)?;
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, claimable, offer.token_decimals)?;
         
-        // Note: desk.token_reserved is deprecated - multi-token model uses per-token treasury balances
-        offer.fulfilled = true;
-        emit!(TokensClaimed { offer: offer_key, beneficiary: offer.beneficiary, amount: offer.token_amount });
+        let registry = &mut ctx.accounts.token_registry;
+        registry.reserved_amount = registry.reserved_amount.checked_sub(claimable).ok_or(OtcError::Overflow)?;
+        offer.claimed_amount = offer.claimed_amount.checked_add(claimable).ok_or(OtcError::Overflow)?;
+        offer.fulfilled = offer.claimed_amount == offer.token_amount;
+        emit!(TokensClaimed { offer: offer_key, beneficiary: offer.beneficiary, amount: claimable });
+        Ok(())
+    }
+
+    /// Deliver a filler's pro rata share of what the offer has vested; permissionless since tokens can only go to the filler
+    pub fn claim_fill(ctx: Context<ClaimFill>, _offer_id: u64) -> Result<()> {
+        let desk = &ctx.accounts.desk;
+        require!(!desk.paused, OtcError::Paused);
+        
+        let offer_key = ctx.accounts.offer.key();
+        let offer = &mut ctx.accounts.offer;
+        require!(!offer.cancelled && !offer.fulfilled, OtcError::BadState);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= offer.unlock_time, OtcError::Locked);
+        let fill = &mut ctx.accounts.offer_fill;
+        let entitled = safe_u128_to_u64(mul_div_u128(vested_amount(offer, now)? as u128, fill.token_amount as u128, offer.token_amount as u128)?)?;
+        let claimable = entitled.checked_sub(fill.claimed_amount).ok_or(OtcError::Overflow)?;
+        require!(claimable > 0, OtcError::Locked);
+        
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.desk_token_treasury.to_account_info(),
+            to: ctx.accounts.payer_token_ata.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, claimable, offer.token_decimals)?;
+        
+        let registry = &mut ctx.accounts.token_registry;
+        registry.reserved_amount = registry.reserved_amount.checked_sub(claimable).ok_or(OtcError::Overflow)?;
+        fill.claimed_amount = fill.claimed_amount.checked_add(claimable).ok_or(OtcError::Overflow)?;
+        offer.claimed_amount = offer.claimed_amount.checked_add(claimable).ok_or(OtcError::Overflow)?;
+        offer.fulfilled = offer.paid && offer.claimed_amount == offer.token_amount;
+        emit!(TokensClaimed { offer: offer_key, beneficiary: fill.payer, amount: claimable });
         Ok(())
     }
 
     /// Withdraw tokens from desk treasury for any registered token
     pub fn withdraw_tokens(ctx: Context<WithdrawTokens>, amount: u64) -> Result<()> {
-        // Desk keypair signs to authorize withdrawal
-        only_owner(&ctx.accounts.desk, &ctx.accounts.owner.key())?;
-        require!(ctx.accounts.desk_signer.key() == ctx.accounts.desk.key(), OtcError::NotOwner);
+        let action = ProposalAction::WithdrawTokens { token_mint: ctx.accounts.token_mint.key(), amount, to: ctx.accounts.owner_token_ata.key() };
+        authorize_treasury_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, action)?;
         require!(ctx.accounts.token_registry.is_active, OtcError::BadState);
-        // No reserved amount check - multi-token model uses treasury balance as source of truth
+        // Only desk-owned inventory can leave; consigned and reserved tokens belong to others
+        let registry = &mut ctx.accounts.token_registry;
+        require!(amount <= registry.desk_owned_amount, OtcError::InsuffInv);
+        let owed = registry.consigned_amount.checked_add(registry.reserved_amount).ok_or(OtcError::Overflow)?;
+        require!(ctx.accounts.desk_token_treasury.amount.saturating_sub(amount) >= owed, OtcError::InsuffInv);
+        registry.desk_owned_amount -= amount;
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.desk_token_treasury.to_account_info(),
             to: ctx.accounts.owner_token_ata.to_account_info(),
-            authority: ctx.accounts.desk_signer.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
             mint: ctx.accounts.token_mint.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
         transfer_checked(cpi_ctx, amount, ctx.accounts.token_registry.decimals)?;
         Ok(())
     }
 
+    /// Withdraw desk funds held in an SPL payment mint treasury
     pub fn withdraw_usdc(ctx: Context<WithdrawUsdc>, amount: u64) -> Result<()> {
-        // Desk keypair signs to authorize withdrawal
-        only_owner(&ctx.accounts.desk, &ctx.accounts.owner.key())?;
-        require!(ctx.accounts.desk_signer.key() == ctx.accounts.desk.key(), OtcError::NotOwner);
+        let action = ProposalAction::WithdrawUsdc { payment_mint: ctx.accounts.payment_mint.key(), amount, to: ctx.accounts.to_payment_ata.key() };
+        authorize_treasury_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, action)?;
+        // Consigner proceeds and bid deposits held in the treasury are not desk funds
+        let held = ctx.accounts.payment_registry.consigner_owed.checked_add(ctx.accounts.payment_registry.bid_escrow).ok_or(OtcError::Overflow)?;
+        let available = ctx.accounts.desk_payment_treasury.amount.saturating_sub(held);
+        require!(amount <= available, OtcError::InsuffInv);
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
         let cpi_accounts = TransferChecked {
-            from: ctx.accounts.desk_usdc_treasury.to_account_info(),
-            to: ctx.accounts.to_usdc_ata.to_account_info(),
-            authority: ctx.accounts.desk_signer.to_account_info(),
-            mint: ctx.accounts.usdc_mint.to_account_info(),
+            from: ctx.accounts.desk_payment_treasury.to_account_info(),
+            to: ctx.accounts.to_payment_ata.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        transfer_checked(cpi_ctx, amount, ctx.accounts.desk.usdc_decimals)?;
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, amount, ctx.accounts.payment_registry.decimals)?;
         Ok(())
     }
 
     pub fn withdraw_sol(ctx: Context<WithdrawSol>, lamports: u64) -> Result<()> {
-        // Desk keypair signs to authorize withdrawal
-        only_owner(&ctx.accounts.desk, &ctx.accounts.owner.key())?;
-        require!(ctx.accounts.desk_signer.key() == ctx.accounts.desk.key(), OtcError::NotOwner);
+        // Desk is program-owned, so lamports move without a desk signature
+        let action = ProposalAction::WithdrawSol { lamports, to: ctx.accounts.to.key() };
+        authorize_treasury_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &ctx.accounts.role, &mut ctx.accounts.proposal, action)?;
         // keep rent-exempt minimum
         let rent = Rent::get()?;
         let min_rent = rent.minimum_balance(8 + Desk::SIZE);
         let current = ctx.accounts.desk.to_account_info().lamports();
         let after = current.checked_sub(lamports).ok_or(OtcError::Overflow)?;
         require!(after >= min_rent, OtcError::BadState);
+        // Consigner proceeds held by the desk are not desk funds
+        require!(after - min_rent >= ctx.accounts.desk.consigner_sol_owed, OtcError::InsuffInv);
         
-        **ctx.accounts.desk.to_account_info().try_borrow_mut_lamports()? -= lamports;
-        **ctx.accounts.to.to_account_info().try_borrow_mut_lamports()? += lamports;
+        move_lamports(&ctx.accounts.desk.to_account_info(), &ctx.accounts.to.to_account_info(), lamports)?;
+        Ok(())
+    }
+
+    /// Withdraw all sale proceeds owed to the calling consigner in an SPL payment mint
+    pub fn withdraw_proceeds_usdc(ctx: Context<WithdrawProceedsUsdc>) -> Result<()> {
+        let proceeds = &mut ctx.accounts.consigner_proceeds;
+        let amount = proceeds.owed;
+        require!(amount > 0, OtcError::AmountRange);
+        proceeds.owed = 0;
+        let payment_registry = &mut ctx.accounts.payment_registry;
+        payment_registry.consigner_owed = payment_registry.consigner_owed.checked_sub(amount).ok_or(OtcError::Overflow)?;
+
+        let desk_key = ctx.accounts.desk.key();
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.desk_payment_treasury.to_account_info(),
+            to: ctx.accounts.consigner_payment_ata.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, amount, payment_registry.decimals)?;
+        emit!(ConsignerProceedsWithdrawn { consigner: ctx.accounts.consigner.key(), amount, payment_mint: payment_registry.mint });
         Ok(())
     }
 
-    pub fn set_emergency_refund(ctx: Context<OnlyOwnerDesk>, enabled: bool, deadline_secs: i64) -> Result<()> {
+    /// Withdraw all SOL sale proceeds owed to the calling consigner
+    pub fn withdraw_proceeds_sol(ctx: Context<WithdrawProceedsSol>) -> Result<()> {
+        let proceeds = &mut ctx.accounts.consigner_proceeds;
+        let lamports = proceeds.owed;
+        require!(lamports > 0, OtcError::AmountRange);
+        proceeds.owed = 0;
         let desk = &mut ctx.accounts.desk;
-        desk.emergency_refund_enabled = enabled;
-        desk.emergency_refund_deadline_secs = deadline_secs;
+        desk.consigner_sol_owed = desk.consigner_sol_owed.checked_sub(lamports).ok_or(OtcError::Overflow)?;
+
+        move_lamports(&desk.to_account_info(), &ctx.accounts.consigner.to_account_info(), lamports)?;
+        emit!(ConsignerProceedsWithdrawn { consigner: ctx.accounts.consigner.key(), amount: lamports, payment_mint: NATIVE_SOL });
+        Ok(())
+    }
+
+    /// Open a consigner's proceeds ledger for a payment mint (NATIVE_SOL for lamports)
+    /// Permissionless: the ledger can only ever pay out to the consigner
+    pub fn open_consigner_proceeds(ctx: Context<OpenConsignerProceeds>, payment_mint: Pubkey) -> Result<()> {
+        let proceeds = &mut ctx.accounts.consigner_proceeds;
+        proceeds.desk = ctx.accounts.desk.key();
+        proceeds.consigner = ctx.accounts.consigner.key();
+        proceeds.payment_mint = payment_mint;
+        proceeds.owed = 0;
+        Ok(())
+    }
+
+    /// Register an SPL mint that buyers may pay with (owner or Registrar)
+    /// Pegged mints are priced at $1; others use the given Pyth feed
+    pub fn register_payment_mint(ctx: Context<RegisterPaymentMint>, price_feed_id: [u8; 32], is_pegged: bool) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::Registrar)?;
+        require!(is_pegged || price_feed_id != [0u8; 32], OtcError::FeedNotConfigured);
+        let registry = &mut ctx.accounts.payment_registry;
+        registry.desk = ctx.accounts.desk.key();
+        registry.mint = ctx.accounts.payment_mint.key();
+        registry.decimals = ctx.accounts.payment_mint.decimals;
+        registry.treasury = ctx.accounts.desk_payment_treasury.key();
+        registry.price_feed_id = price_feed_id;
+        registry.is_pegged = is_pegged;
+        registry.usd_price_8d = if is_pegged { USD_PEG_8D } else { 0 };
+        registry.prices_updated_at = Clock::get()?.unix_timestamp;
+        registry.is_active = true;
+        registry.consigner_owed = 0;
+        registry.bid_escrow = 0;
+        emit!(PaymentMintRegistered { desk: registry.desk, mint: registry.mint, treasury: registry.treasury, is_pegged });
         Ok(())
     }
 
-    /// Set P2P commission rate for non-negotiable fixed-price deals
-    /// Default is 25 bps (0.25%), max 500 bps (5%)
-    pub fn set_p2p_commission(ctx: Context<OnlyOwnerDesk>, bps: u16) -> Result<()> {
-        require!(bps <= 500, OtcError::CommissionRange); // Max 5% for P2P
+    pub fn set_payment_mint_active(ctx: Context<SetPaymentMintActive>, active: bool) -> Result<()> {
+        require_role(&ctx.accounts.desk, &ctx.accounts.authority.key(), &ctx.accounts.role, Role::Registrar)?;
+        ctx.accounts.payment_registry.is_active = active;
+        Ok(())
+    }
+
+    /// Refresh a non-pegged payment mint's USD price from its Pyth feed (permissionless)
+    pub fn update_payment_price_from_pyth(ctx: Context<UpdatePaymentPriceFromPyth>, max_price_deviation_bps: u16) -> Result<()> {
+        let registry = &mut ctx.accounts.payment_registry;
+        let desk = &ctx.accounts.desk;
+        require!(!registry.is_pegged, OtcError::BadState);
+        let clock = Clock::get()?;
+        require!(desk.max_price_age_secs >= 0, OtcError::AmountRange);
+        // SAFETY: require! above ensures max_price_age_secs >= 0
+        #[allow(clippy::cast_sign_loss)]
+        let max_age = desk.max_price_age_secs as u64;
+        let price = ctx.accounts.price_feed
+            .get_price_no_older_than(&clock, max_age, &registry.price_feed_id)
+            .map_err(|_| OtcError::StalePrice)?;
+        let usd_8d = convert_pyth_price(price.price, price.exponent)?;
+        check_price_deviation(registry.usd_price_8d, usd_8d, max_price_deviation_bps)?;
+        registry.usd_price_8d = usd_8d;
+        registry.prices_updated_at = clock.unix_timestamp;
+        Ok(())
+    }
+
+    pub fn set_emergency_refund(ctx: Context<OwnerAction>, enabled: bool, deadline_secs: i64) -> Result<()> {
+        authorize_owner_action(&ctx.accounts.desk, &ctx.accounts.owner.key(), &mut ctx.accounts.proposal, ProposalAction::SetEmergencyRefund { enabled, deadline_secs })?;
         let desk = &mut ctx.accounts.desk;
-        desk.p2p_commission_bps = bps;
+        desk.emergency_refund_enabled = enabled;
+        desk.emergency_refund_deadline_secs = deadline_secs;
         Ok(())
     }
 
     pub fn emergency_refund_sol(ctx: Context<EmergencyRefundSol>, _offer_id: u64) -> Result<()> {
-        let desk = &ctx.accounts.desk;
-        require!(desk.emergency_refund_enabled, OtcError::BadState);
-        
+        let desk_key = ctx.accounts.desk.key();
         let offer = &mut ctx.accounts.offer;
         require!(offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
-        require!(offer.currency == 0, OtcError::BadState); // SOL payment
-        
-        let now = Clock::get()?.unix_timestamp;
-        let deadline = offer.created_at.checked_add(desk.emergency_refund_deadline_secs).ok_or(OtcError::Overflow)?;
-        let unlock_deadline = offer.unlock_time.checked_add(30 * 86400).ok_or(OtcError::Overflow)?; // 30 days after unlock
-        
-        require!(now >= deadline || now >= unlock_deadline, OtcError::TooEarlyForRefund);
-        
-        let caller = ctx.accounts.caller.key();
-        require!(
-            caller == offer.payer || 
-            caller == offer.beneficiary || 
-            caller == desk.owner || 
-            caller == desk.agent || 
-            desk.approvers.contains(&caller),
-            OtcError::NotOwner
-        );
+        require!(offer.claimed_amount == 0, OtcError::BadState); // Partially vested offers cannot be refunded
+        require!(offer.fill_count == 0, OtcError::BadState); // Syndicated offers settle per fill
+        require!(offer.payment_mint == NATIVE_SOL, OtcError::BadState); // SOL payment
+        check_emergency_refund(&ctx.accounts.desk, offer, offer.payer, ctx.accounts.caller.key(), &ctx.accounts.approver_account)?;
         
         // Mark as cancelled to prevent double refund
         offer.cancelled = true;
         
-        // Note: desk.token_reserved is deprecated - multi-token model doesn't use it
+        let refund = offer.amount_paid;
+        unwind_offer_payment(
+            offer,
+            desk_key,
+            offer.token_amount,
+            offer.consigner_proceeds,
+            &mut ctx.accounts.token_registry,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut ctx.accounts.desk.consigner_sol_owed,
+        )?;
         
         // Refund SOL to payer
-        **ctx.accounts.desk.to_account_info().try_borrow_mut_lamports()? -= offer.amount_paid;
-        **ctx.accounts.payer_refund.to_account_info().try_borrow_mut_lamports()? += offer.amount_paid;
+        move_lamports(&ctx.accounts.desk.to_account_info(), &ctx.accounts.payer_refund.to_account_info(), refund)?;
         
         Ok(())
     }
 
     pub fn emergency_refund_usdc(ctx: Context<EmergencyRefundUsdc>, _offer_id: u64) -> Result<()> {
-        let desk = &ctx.accounts.desk;
-        require!(desk.emergency_refund_enabled, OtcError::BadState);
-        
+        let desk_key = ctx.accounts.desk.key();
         let offer = &mut ctx.accounts.offer;
         require!(offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
-        require!(offer.currency == 1, OtcError::BadState); // USDC payment
+        require!(offer.claimed_amount == 0, OtcError::BadState); // Partially vested offers cannot be refunded
+        require!(offer.fill_count == 0, OtcError::BadState); // Syndicated offers settle per fill
+        require!(offer.payment_mint != NATIVE_SOL, OtcError::BadState); // SPL payment
+        check_emergency_refund(&ctx.accounts.desk, offer, offer.payer, ctx.accounts.caller.key(), &ctx.accounts.approver_account)?;
         
-        let now = Clock::get()?.unix_timestamp;
-        let deadline = offer.created_at.checked_add(desk.emergency_refund_deadline_secs).ok_or(OtcError::Overflow)?;
-        let unlock_deadline = offer.unlock_time.checked_add(30 * 86400).ok_or(OtcError::Overflow)?;
+        // Mark as cancelled
+        offer.cancelled = true;
         
-        require!(now >= deadline || now >= unlock_deadline, OtcError::TooEarlyForRefund);
+        let refund = offer.amount_paid;
+        unwind_offer_payment(
+            offer,
+            desk_key,
+            offer.token_amount,
+            offer.consigner_proceeds,
+            &mut ctx.accounts.token_registry,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut ctx.accounts.payment_registry.consigner_owed,
+        )?;
         
-        let caller = ctx.accounts.caller.key();
-        require!(
-            caller == offer.payer || 
-            caller == offer.beneficiary || 
-            caller == desk.owner || 
-            caller == desk.agent || 
-            desk.approvers.contains(&caller),
-            OtcError::NotOwner
-        );
+        // Refund payment to payer
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.desk_payment_treasury.to_account_info(),
+            to: ctx.accounts.payer_payment_refund.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, refund, ctx.accounts.payment_registry.decimals)?;
         
-        // Mark as cancelled
-        offer.cancelled = true;
+        Ok(())
+    }
+
+    /// Emergency refund of one payer's tranche of a syndicated offer paid in SOL
+    pub fn emergency_refund_fill_sol(ctx: Context<EmergencyRefundFillSol>, _offer_id: u64) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let offer = &mut ctx.accounts.offer;
+        let fill = &mut ctx.accounts.offer_fill;
+        require!(offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
+        require!(fill.amount_paid > 0 && fill.claimed_amount == 0, OtcError::BadState); // Refunded or partially vested tranches cannot be refunded
+        require!(offer.payment_mint == NATIVE_SOL, OtcError::BadState); // SOL payment
+        check_emergency_refund(&ctx.accounts.desk, offer, fill.payer, ctx.accounts.caller.key(), &ctx.accounts.approver_account)?;
+        
+        let refund = fill.amount_paid;
+        unwind_offer_payment(
+            offer,
+            desk_key,
+            fill.token_amount,
+            fill.consigner_proceeds,
+            &mut ctx.accounts.token_registry,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut ctx.accounts.desk.consigner_sol_owed,
+        )?;
+        remove_refunded_fill(offer, fill)?;
+        
+        // Refund SOL to the tranche's payer
+        move_lamports(&ctx.accounts.desk.to_account_info(), &ctx.accounts.payer_refund.to_account_info(), refund)?;
+        
+        Ok(())
+    }
+
+    /// Emergency refund of one payer's tranche of a syndicated offer paid with an SPL mint
+    pub fn emergency_refund_fill_usdc(ctx: Context<EmergencyRefundFillUsdc>, _offer_id: u64) -> Result<()> {
+        let desk_key = ctx.accounts.desk.key();
+        let offer = &mut ctx.accounts.offer;
+        let fill = &mut ctx.accounts.offer_fill;
+        require!(offer.paid && !offer.fulfilled && !offer.cancelled, OtcError::BadState);
+        require!(fill.amount_paid > 0 && fill.claimed_amount == 0, OtcError::BadState); // Refunded or partially vested tranches cannot be refunded
+        require!(offer.payment_mint != NATIVE_SOL, OtcError::BadState); // SPL payment
+        check_emergency_refund(&ctx.accounts.desk, offer, fill.payer, ctx.accounts.caller.key(), &ctx.accounts.approver_account)?;
         
-        // Note: desk.token_reserved is deprecated - multi-token model doesn't use it
+        let refund = fill.amount_paid;
+        unwind_offer_payment(
+            offer,
+            desk_key,
+            fill.token_amount,
+            fill.consigner_proceeds,
+            &mut ctx.accounts.token_registry,
+            &mut ctx.accounts.consignment,
+            &mut ctx.accounts.consigner_proceeds,
+            &mut ctx.accounts.payment_registry.consigner_owed,
+        )?;
+        remove_refunded_fill(offer, fill)?;
         
-        // Refund USDC to payer
+        // Refund payment to the tranche's payer
+        let bump = [ctx.accounts.desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
         let cpi_accounts = TransferChecked {
-            from: ctx.accounts.desk_usdc_treasury.to_account_info(),
-            to: ctx.accounts.payer_usdc_refund.to_account_info(),
-            authority: ctx.accounts.desk_signer.to_account_info(),
-            mint: ctx.accounts.usdc_mint.to_account_info(),
+            from: ctx.accounts.desk_payment_treasury.to_account_info(),
+            to: ctx.accounts.payer_payment_refund.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        transfer_checked(cpi_ctx, offer.amount_paid, ctx.accounts.desk.usdc_decimals)?;
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, refund, ctx.accounts.payment_registry.decimals)?;
         
         Ok(())
     }
@@ -1190,6 +2440,9 @@ pub struct InitDesk<'info> {
     pub system_program: Program<'info, System>,
     #[account(init, payer = payer, space = 8 + Desk::SIZE)]
     pub desk: Account<'info, Desk>,
+    /// CHECK: PDA that will own the desk treasuries; holds no data
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -1202,7 +2455,7 @@ pub struct RegisterToken<'info> {
         init, 
         payer = payer, 
         space = 8 + TokenRegistry::SIZE,
-        seeds = [b"registry", desk.key().as_ref(), token_mint.key().as_ref()],
+        seeds = [REGISTRY_SEED, desk.key().as_ref(), token_mint.key().as_ref()],
         bump
     )]
     pub token_registry: Account<'info, TokenRegistry>,
@@ -1218,44 +2471,92 @@ pub struct CreateConsignment<'info> {
     pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(mut, constraint = consigner_token_ata.mint == token_mint.key() @ OtcError::BadState, constraint = consigner_token_ata.owner == consigner.key() @ OtcError::BadState)]
     pub consigner_token_ata: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = desk_token_treasury.mint == token_mint.key() @ OtcError::BadState, constraint = desk_token_treasury.owner == desk.key() @ OtcError::BadState)]
+    #[account(mut, constraint = desk_token_treasury.mint == token_mint.key() @ OtcError::BadState, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
     pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(init, payer = consigner, space = 8 + Consignment::SIZE)]
+    /// Token registry - tracks consigned inventory for this mint
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == token_mint.key() @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(
+        init,
+        payer = consigner,
+        space = 8 + Consignment::SIZE,
+        seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &desk.next_consignment_id.to_le_bytes()],
+        bump
+    )]
     pub consignment: Account<'info, Consignment>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
+#[instruction(consignment_id: u64)]
 pub struct CreateOfferFromConsignment<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState)]
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
     pub consignment: Account<'info, Consignment>,
     #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
     pub token_registry: Account<'info, TokenRegistry>,
+    /// Payment mint registry (required unless paying in NATIVE_SOL)
+    pub payment_registry: Option<Account<'info, PaymentRegistry>>,
     #[account(mut)]
     pub beneficiary: Signer<'info>,
-    #[account(init_if_needed, payer = beneficiary, space = 8 + Offer::SIZE)]
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
     pub offer: Account<'info, Offer>,
     pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
 }
 
 #[derive(Accounts)]
-pub struct SetTokenOracleFeed<'info> {
-    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
-    pub token_registry: Account<'info, TokenRegistry>,
+#[instruction(quote: AgentQuote)]
+pub struct CreateOfferFromQuote<'info> {
+    #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(constraint = owner.key() == desk.owner @ OtcError::NotOwner)]
-    pub owner: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct SetTokenPoolConfig<'info> {
-    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &quote.consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
     pub token_registry: Account<'info, TokenRegistry>,
-    pub desk: Account<'info, Desk>,
-    pub signer: Signer<'info>, // Can be owner or registered_by
+    /// Payment mint registry (required unless paying in NATIVE_SOL)
+    pub payment_registry: Option<Account<'info, PaymentRegistry>>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    /// Created on redemption, so a quote nonce can never be used twice
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + QuoteNonce::SIZE,
+        seeds = [QUOTE_NONCE_SEED, desk.key().as_ref(), &quote.nonce.to_le_bytes()],
+        bump
+    )]
+    pub quote_nonce: Account<'info, QuoteNonce>,
+    /// The agent's approval slot, taken by the quote so the agent cannot approve the offer again
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + OfferApproval::SIZE,
+        seeds = [APPROVAL_SEED, offer.key().as_ref(), desk.agent.as_ref()],
+        bump
+    )]
+    pub offer_approval: Account<'info, OfferApproval>,
+    /// CHECK: Instructions sysvar, used to read the preceding ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -1263,18 +2564,9 @@ pub struct SetManualTokenPrice<'info> {
     #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
     pub token_registry: Account<'info, TokenRegistry>,
     pub desk: Account<'info, Desk>,
-    #[account(constraint = owner.key() == desk.owner @ OtcError::NotOwner)]
-    pub owner: Signer<'info>,
-}
-
-/// Configure pool oracle security settings (owner only)
-#[derive(Accounts)]
-pub struct ConfigurePoolOracle<'info> {
-    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
-    pub token_registry: Account<'info, TokenRegistry>,
-    pub desk: Account<'info, Desk>,
-    #[account(constraint = owner.key() == desk.owner @ OtcError::NotOwner)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+    /// PriceSetter grant (not needed when a single owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
 }
 
 #[derive(Accounts)]
@@ -1291,15 +2583,22 @@ pub struct UpdateTokenPriceFromPool<'info> {
     pub vault_a: InterfaceAccount<'info, TokenAccount>,
     /// Quote vault (vault_b) - contains USDC or SOL
     /// NOTE: Quote vault ownership is verified via AMM program ID check in instruction logic
+    #[account(constraint = vault_b.mint == quote_mint.key() @ OtcError::BadState)]
     pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    /// Quote vault's mint, for its decimals
+    pub quote_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
 }
 
 /// PumpSwap / Pump.fun bonding curve price update
 #[derive(Accounts)]
 pub struct UpdateTokenPriceFromPumpswap<'info> {
-    #[account(mut, constraint = token_registry.pool_type == PoolType::PumpSwap @ OtcError::BadState)]
+    #[account(mut, constraint = token_registry.pool_type == PoolType::PumpSwap @ OtcError::BadState, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
     pub token_registry: Account<'info, TokenRegistry>,
+    pub desk: Account<'info, Desk>,
+    pub authority: Signer<'info>,
+    /// PriceSetter grant (not needed when a single owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
     /// CHECK: Validated against registry.pool_address (bonding curve account)
     #[account(constraint = bonding_curve.key() == token_registry.pool_address @ OtcError::BadState)]
     pub bonding_curve: UncheckedAccount<'info>,
@@ -1322,206 +2621,1066 @@ pub struct UpdateTokenPriceFromPyth<'info> {
 }
 
 #[derive(Accounts)]
-pub struct OnlyOwnerDesk<'info> {
+pub struct TransferOwnership<'info> {
     pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+    #[account(mut)]
     pub desk: Account<'info, Desk>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
 }
 
+/// Action gated by a named role (a single owner holds every role)
 #[derive(Accounts)]
-pub struct UpdatePricesFromPyth<'info> {
+pub struct DeskRoleAction<'info> {
+    pub authority: Signer<'info>,
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    /// Pyth price feed account for token/USD
-    pub token_price_feed: Account<'info, PriceUpdateV2>,
-    /// Pyth price feed account for SOL/USD
-    pub sol_price_feed: Account<'info, PriceUpdateV2>,
-    /// Anyone can update prices from oracle
-    pub payer: Signer<'info>,
+    /// Role grant for the signer (not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
 }
 
 #[derive(Accounts)]
-pub struct DepositTokens<'info> {
-    #[account(mut)]
+#[instruction(approver: Pubkey)]
+pub struct SetApprover<'info> {
     pub desk: Account<'info, Desk>,
-    /// Token registry - must belong to this desk
-    #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
-    pub token_registry: Account<'info, TokenRegistry>,
-    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut, constraint = owner_token_ata.mint == token_registry.token_mint, constraint = owner_token_ata.owner == owner.key())]
-    pub owner_token_ata: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = desk_token_treasury.mint == token_registry.token_mint, constraint = desk_token_treasury.owner == desk.key())]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    pub token_program: Interface<'info, TokenInterface>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// ApproverAdmin grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Approver::SIZE,
+        seeds = [APPROVER_SEED, desk.key().as_ref(), approver.as_ref()],
+        bump
+    )]
+    pub approver_account: Account<'info, Approver>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateOffer<'info> {
+#[instruction(approver: Pubkey)]
+pub struct RemoveApprover<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
     #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// ApproverAdmin grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    #[account(mut, seeds = [APPROVER_SEED, desk.key().as_ref(), approver.as_ref()], bump, close = owner)]
+    pub approver_account: Account<'info, Approver>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
     pub desk: Account<'info, Desk>,
-    /// Token registry for pricing - must belong to this desk
-    #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
-    pub token_registry: Account<'info, TokenRegistry>,
-    #[account(mut, constraint = desk_token_treasury.mint == token_registry.token_mint, constraint = desk_token_treasury.owner == desk.key())]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub beneficiary: Signer<'info>,
-    #[account(init_if_needed, payer = beneficiary, space = 8 + Offer::SIZE)]
-    pub offer: Account<'info, Offer>,
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RoleGrant::SIZE,
+        seeds = [ROLE_SEED, desk.key().as_ref(), &[role as u8], holder.as_ref()],
+        bump
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ApproveOffer<'info> {
+#[instruction(role: Role, holder: Pubkey)]
+pub struct RevokeRole<'info> {
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    /// Consignment account - required for negotiable check
-    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
-    pub consignment: Account<'info, Consignment>,
-    pub approver: Signer<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    #[account(mut, seeds = [ROLE_SEED, desk.key().as_ref(), &[role as u8], holder.as_ref()], bump, close = owner)]
+    pub role_grant: Account<'info, RoleGrant>,
 }
 
+/// Sensitive owner action: signed by the owner, or by an owner-set member with an approved proposal
 #[derive(Accounts)]
-pub struct CancelOffer<'info> {
+pub struct OwnerAction<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    pub caller: Signer<'info>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
 }
 
+/// Approval-policy change: owner or ApproverAdmin in single-owner mode, otherwise an approved proposal
 #[derive(Accounts)]
-pub struct CancelOfferWithConsignment<'info> {
+pub struct ApproverAdminAction<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
-    pub consignment: Account<'info, Consignment>,
-    pub caller: Signer<'info>,
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// ApproverAdmin grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
 }
 
 #[derive(Accounts)]
-pub struct FulfillOfferUsdc<'info> {
+pub struct QueueParamChange<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    pub usdc_mint: InterfaceAccount<'info, Mint>,
-    /// Token treasury - must match the token_mint in the offer
-    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.key())]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = desk_usdc_treasury.mint == desk.usdc_mint, constraint = desk_usdc_treasury.owner == desk.key())]
-    pub desk_usdc_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = payer_usdc_ata.mint == desk.usdc_mint, constraint = payer_usdc_ata.owner == payer.key())]
-    pub payer_usdc_ata: InterfaceAccount<'info, TokenAccount>,
-    /// Agent USDC account for receiving commission (optional - only needed if commission > 0)
-    /// SECURITY: Validated in instruction to be owned by desk.agent to prevent commission theft
     #[account(mut)]
-    pub agent_usdc_ata: Option<InterfaceAccount<'info, TokenAccount>>,
-    /// Desk signer for authorizing commission transfer from treasury
-    pub desk_signer: Signer<'info>,
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + PendingParamChange::SIZE,
+        seeds = [PARAM_CHANGE_SEED, desk.key().as_ref(), &desk.next_param_change_id.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingParamChange>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct FulfillOfferSol<'info> {
+#[instruction(change_id: u64)]
+pub struct ExecuteParamChange<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    /// Token treasury - must match the token_mint in the offer
-    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.key())]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    /// Agent account for receiving SOL commission (optional - only needed if commission > 0)
-    /// CHECK: This is the agent's wallet address, we're just sending SOL to it
     #[account(mut)]
-    pub agent: Option<AccountInfo<'info>>,
-    /// Desk signer for authorizing lamport transfer
-    pub desk_signer: Signer<'info>,
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    #[account(mut, seeds = [PARAM_CHANGE_SEED, desk.key().as_ref(), &change_id.to_le_bytes()], bump, close = owner)]
+    pub pending_change: Account<'info, PendingParamChange>,
+    /// Target registry (required for token oracle changes)
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
+    pub token_registry: Option<Account<'info, TokenRegistry>>,
 }
 
 #[derive(Accounts)]
-pub struct Claim<'info> {
-    #[account(mut)]
+#[instruction(change_id: u64)]
+pub struct CancelParamChange<'info> {
     pub desk: Account<'info, Desk>,
-    pub desk_signer: Signer<'info>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
-    pub offer: Account<'info, Offer>,
-    pub token_mint: InterfaceAccount<'info, Mint>,
-    /// Treasury must match the token in the offer and be owned by desk
-    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.key() @ OtcError::BadState)]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = beneficiary_token_ata.mint == offer.token_mint, constraint = beneficiary_token_ata.owner == offer.beneficiary @ OtcError::BadState)]
-    pub beneficiary_token_ata: InterfaceAccount<'info, TokenAccount>,
-    /// CHECK: Validated against offer.beneficiary in instruction
-    pub beneficiary: UncheckedAccount<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    #[account(mut, seeds = [PARAM_CHANGE_SEED, desk.key().as_ref(), &change_id.to_le_bytes()], bump, close = owner)]
+    pub pending_change: Account<'info, PendingParamChange>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawTokens<'info> {
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + OwnerProposal::SIZE,
+        seeds = [PROPOSAL_SEED, desk.key().as_ref(), &desk.next_proposal_id.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, OwnerProposal>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ApproveProposal<'info> {
+    pub desk: Account<'info, Desk>,
+    pub owner: Signer<'info>,
+    #[account(mut, seeds = [PROPOSAL_SEED, desk.key().as_ref(), &proposal_id.to_le_bytes()], bump)]
+    pub proposal: Account<'info, OwnerProposal>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ProposerAction<'info> {
+    pub desk: Account<'info, Desk>,
+    pub proposer: Signer<'info>,
+    #[account(mut, seeds = [PROPOSAL_SEED, desk.key().as_ref(), &proposal_id.to_le_bytes()], bump, has_one = proposer)]
+    pub proposal: Account<'info, OwnerProposal>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CloseProposal<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(mut, seeds = [PROPOSAL_SEED, desk.key().as_ref(), &proposal_id.to_le_bytes()], bump, has_one = proposer, close = proposer)]
+    pub proposal: Account<'info, OwnerProposal>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    pub pending_owner: Signer<'info>,
+    #[account(mut, constraint = desk.pending_owner == pending_owner.key() @ OtcError::NotOwner)]
+    pub desk: Account<'info, Desk>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePricesFromPyth<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// Pyth price feed account for token/USD
+    pub token_price_feed: Account<'info, PriceUpdateV2>,
+    /// Pyth price feed account for SOL/USD
+    pub sol_price_feed: Account<'info, PriceUpdateV2>,
+    /// Anyone can update prices from oracle
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// Token registry - must belong to this desk
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == token_mint.key() @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+    #[account(mut, constraint = owner_token_ata.mint == token_registry.token_mint, constraint = owner_token_ata.owner == owner.key())]
+    pub owner_token_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = desk_token_treasury.mint == token_registry.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOffer<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// Token registry for pricing and inventory reservation - must belong to this desk
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    /// Payment mint registry (required unless paying in NATIVE_SOL)
+    pub payment_registry: Option<Account<'info, PaymentRegistry>>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct SetOfferSyndicated<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump, has_one = beneficiary)]
+    pub offer: Account<'info, Offer>,
+    pub beneficiary: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct ApproveOffer<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    /// Consignment account - required for negotiable check
+    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut)]
+    pub approver: Signer<'info>,
+    /// Approver record with the signer's limits (not needed for the desk agent)
+    #[account(mut)]
+    pub approver_account: Option<Account<'info, Approver>>,
+    #[account(
+        init,
+        payer = approver,
+        space = 8 + OfferApproval::SIZE,
+        seeds = [APPROVAL_SEED, offer.key().as_ref(), approver.key().as_ref()],
+        bump
+    )]
+    pub offer_approval: Account<'info, OfferApproval>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
+    pub offer: Account<'info, Offer>,
+    /// Token registry - releases the offer's reserved inventory
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    pub caller: Signer<'info>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOfferWithConsignment<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
+    pub offer: Account<'info, Offer>,
+    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Account<'info, Consignment>,
+    pub caller: Signer<'info>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FulfillOfferUsdc<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(constraint = payment_mint.key() == offer.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    /// Token treasury - must match the token_mint in the offer
+    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = payer_payment_ata.mint == offer.payment_mint, constraint = payer_payment_ata.owner == payer.key())]
+    pub payer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Agent payment-mint account for receiving commission (optional - only needed if commission > 0)
+    /// SECURITY: Validated in instruction to be owned by desk.agent to prevent commission theft
+    #[account(mut, constraint = agent_payment_ata.mint == offer.payment_mint @ OtcError::BadState)]
+    pub agent_payment_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Source consignment (required when offer.consignment_id > 0)
+    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FulfillOfferSol<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    /// Token treasury - must match the token_mint in the offer
+    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    /// Agent account for receiving SOL commission (optional - only needed if commission > 0)
+    /// CHECK: This is the agent's wallet address, we're just sending SOL to it
+    #[account(mut)]
+    pub agent: Option<AccountInfo<'info>>,
+    /// Source consignment (required when offer.consignment_id > 0)
+    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FulfillAndClaimUsdc<'info> {
+    pub fulfill: FulfillOfferUsdc<'info>,
+    #[account(constraint = token_mint.key() == fulfill.offer.token_mint @ OtcError::BadState)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, constraint = beneficiary_token_ata.mint == fulfill.offer.token_mint, constraint = beneficiary_token_ata.owner == fulfill.offer.beneficiary @ OtcError::BadState)]
+    pub beneficiary_token_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Program of the offered token, which may differ from the payment mint's
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FulfillAndClaimSol<'info> {
+    pub fulfill: FulfillOfferSol<'info>,
+    #[account(constraint = token_mint.key() == fulfill.offer.token_mint @ OtcError::BadState)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, constraint = beneficiary_token_ata.mint == fulfill.offer.token_mint, constraint = beneficiary_token_ata.owner == fulfill.offer.beneficiary @ OtcError::BadState)]
+    pub beneficiary_token_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Program of the offered token
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FillOfferUsdc<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + OfferFill::SIZE,
+        seeds = [FILL_SEED, offer.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub offer_fill: Account<'info, OfferFill>,
+    #[account(constraint = payment_mint.key() == offer.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = payer_payment_ata.mint == offer.payment_mint, constraint = payer_payment_ata.owner == payer.key())]
+    pub payer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Agent payment-mint account for receiving commission (optional - only needed if commission > 0)
+    #[account(mut, constraint = agent_payment_ata.mint == offer.payment_mint @ OtcError::BadState)]
+    pub agent_payment_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Source consignment (required when offer.consignment_id > 0)
+    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct FillOfferSol<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + OfferFill::SIZE,
+        seeds = [FILL_SEED, offer.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub offer_fill: Account<'info, OfferFill>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    /// Agent account for receiving SOL commission (optional - only needed if commission > 0)
+    /// CHECK: This is the agent's wallet address, we're just sending SOL to it
+    #[account(mut)]
+    pub agent: Option<AccountInfo<'info>>,
+    /// Source consignment (required when offer.consignment_id > 0)
+    #[account(constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct ClaimFill<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(mut, constraint = offer_fill.offer == offer.key() @ OtcError::BadState)]
+    pub offer_fill: Account<'info, OfferFill>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// Treasury must match the token in the offer and be owned by desk
+    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, constraint = payer_token_ata.mint == offer.token_mint, constraint = payer_token_ata.owner == offer_fill.payer @ OtcError::BadState)]
+    pub payer_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// Treasury must match the token in the offer and be owned by desk
+    #[account(mut, constraint = desk_token_treasury.mint == offer.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, constraint = beneficiary_token_ata.mint == offer.token_mint, constraint = beneficiary_token_ata.owner == offer.beneficiary @ OtcError::BadState)]
+    pub beneficiary_token_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Validated against offer.beneficiary in instruction
+    pub beneficiary: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokens<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// Treasurer grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    /// Token registry - must belong to this desk
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == token_mint.key() @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, constraint = desk_token_treasury.mint == token_registry.token_mint, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    /// SECURITY: Validate owner_token_ata is owned by the owner signer to prevent withdrawal theft
+    #[account(mut, constraint = owner_token_ata.mint == token_registry.token_mint, constraint = owner_token_ata.owner == owner.key() @ OtcError::BadState)]
+    pub owner_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUsdc<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// Treasurer grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [PAYMENT_SEED, desk.key().as_ref(), payment_mint.key().as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = to_payment_ata.mint == payment_mint.key() @ OtcError::BadState)]
+    pub to_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct WithdrawConsignment<'info> {
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    pub desk: Account<'info, Desk>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// Token registry - tracks consigned inventory for this mint
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == consignment.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub consigner: Signer<'info>,
+    #[account(mut, constraint = desk_token_treasury.mint == consignment.token_mint @ OtcError::BadState, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = consigner_token_ata.mint == consignment.token_mint @ OtcError::BadState, constraint = consigner_token_ata.owner == consigner.key() @ OtcError::BadState)]
+    pub consigner_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct ExpireConsignment<'info> {
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    pub desk: Account<'info, Desk>,
+    #[account(constraint = token_mint.key() == consignment.token_mint @ OtcError::BadState)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// Token registry - tracks consigned inventory for this mint
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == consignment.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, constraint = desk_token_treasury.mint == consignment.token_mint @ OtcError::BadState, constraint = desk_token_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = consigner_token_ata.mint == consignment.token_mint @ OtcError::BadState, constraint = consigner_token_ata.owner == consignment.consigner @ OtcError::BadState)]
+    pub consigner_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct OpenSealedBidAuction<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump, has_one = consigner)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(
+        init,
+        payer = consigner,
+        space = 8 + SealedBidAuction::SIZE,
+        seeds = [AUCTION_SEED, consignment.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, SealedBidAuction>,
+    #[account(mut)]
+    pub consigner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct CommitSealedBid<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, seeds = [AUCTION_SEED, consignment.key().as_ref()], bump)]
+    pub auction: Account<'info, SealedBidAuction>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + SealedBid::SIZE,
+        seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    #[account(constraint = usdc_mint.key() == desk.usdc_mint @ OtcError::BadState)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), desk.usdc_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_usdc_ata.mint == desk.usdc_mint @ OtcError::BadState, constraint = bidder_usdc_ata.owner == bidder.key() @ OtcError::BadState)]
+    pub bidder_usdc_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the bidder is acting as an approver on a private consignment
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct RevealSealedBid<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, seeds = [AUCTION_SEED, consignment.key().as_ref()], bump)]
+    pub auction: Account<'info, SealedBidAuction>,
+    #[account(mut, seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()], bump)]
+    pub sealed_bid: Account<'info, SealedBid>,
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct SettleSealedBid<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, seeds = [AUCTION_SEED, consignment.key().as_ref()], bump)]
+    pub auction: Account<'info, SealedBidAuction>,
+    #[account(mut, close = bidder, seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()], bump)]
+    pub sealed_bid: Account<'info, SealedBid>,
+    /// CHECK: Bidder wallet, receives the bid account's rent
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == consignment.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(constraint = usdc_mint.key() == desk.usdc_mint @ OtcError::BadState)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), desk.usdc_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_usdc_ata.mint == desk.usdc_mint @ OtcError::BadState, constraint = bidder_usdc_ata.owner == bidder.key() @ OtcError::BadState)]
+    pub bidder_usdc_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    /// Anyone may settle; pays the new offer's rent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct RefundSealedBid<'info> {
     pub desk: Account<'info, Desk>,
-    /// Token registry - must belong to this desk
+    #[account(seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, seeds = [AUCTION_SEED, consignment.key().as_ref()], bump)]
+    pub auction: Account<'info, SealedBidAuction>,
+    #[account(mut, close = bidder, seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()], bump)]
+    pub sealed_bid: Account<'info, SealedBid>,
+    /// CHECK: Bidder wallet, receives the bid account's rent
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(constraint = usdc_mint.key() == desk.usdc_mint @ OtcError::BadState)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), desk.usdc_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_usdc_ata.mint == desk.usdc_mint @ OtcError::BadState, constraint = bidder_usdc_ata.owner == bidder.key() @ OtcError::BadState)]
+    pub bidder_usdc_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Consigner's USDC proceeds ledger (required when forfeiting an unrevealed deposit)
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consignment.consigner.as_ref(), desk.usdc_mint.as_ref()], bump)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct FinishSealedBidAuction<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump, has_one = consigner)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, close = consigner, seeds = [AUCTION_SEED, consignment.key().as_ref()], bump)]
+    pub auction: Account<'info, SealedBidAuction>,
+    /// CHECK: Consigner wallet, opened the auction and receives its rent
+    #[account(mut)]
+    pub consigner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct ForfeitAwardDeposit<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &offer.consignment_id.to_le_bytes()], bump)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consignment.consigner.as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+}
+
+#[derive(Accounts)]
+#[instruction(bid_id: u64)]
+pub struct PlaceBidSol<'info> {
+    pub desk: Account<'info, Desk>,
+    /// Registry of the wanted token
     #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
     pub token_registry: Account<'info, TokenRegistry>,
-    pub token_mint: InterfaceAccount<'info, Mint>,
-    pub desk_signer: Signer<'info>,
-    #[account(mut, constraint = desk_token_treasury.mint == token_registry.token_mint, constraint = desk_token_treasury.owner == desk.key() @ OtcError::BadState)]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    /// SECURITY: Validate owner_token_ata is owned by the owner signer to prevent withdrawal theft
-    #[account(mut, constraint = owner_token_ata.mint == token_registry.token_mint, constraint = owner_token_ata.owner == owner.key() @ OtcError::BadState)]
-    pub owner_token_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::SIZE,
+        seeds = [RFQ_BID_SEED, desk.key().as_ref(), bidder.key().as_ref(), &bid_id.to_le_bytes()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(bid_id: u64)]
+pub struct PlaceBidUsdc<'info> {
+    pub desk: Account<'info, Desk>,
+    /// Registry of the wanted token
+    #[account(constraint = token_registry.desk == desk.key() @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::SIZE,
+        seeds = [RFQ_BID_SEED, desk.key().as_ref(), bidder.key().as_ref(), &bid_id.to_le_bytes()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), payment_mint.key().as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_payment_ata.mint == payment_mint.key() @ OtcError::BadState, constraint = bidder_payment_ata.owner == bidder.key() @ OtcError::BadState)]
+    pub bidder_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawUsdc<'info> {
-    pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+#[instruction(bid_id: u64)]
+pub struct CancelBidSol<'info> {
     pub desk: Account<'info, Desk>,
-    pub usdc_mint: InterfaceAccount<'info, Mint>,
-    #[account(constraint = desk_signer.key() == desk.key() @ OtcError::NotOwner)]
-    pub desk_signer: Signer<'info>,
-    #[account(mut, constraint = desk_usdc_treasury.mint == desk.usdc_mint @ OtcError::BadState, constraint = desk_usdc_treasury.owner == desk.key() @ OtcError::BadState)]
-    pub desk_usdc_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = to_usdc_ata.mint == desk.usdc_mint @ OtcError::BadState)]
-    pub to_usdc_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, close = bidder, seeds = [RFQ_BID_SEED, desk.key().as_ref(), bidder.key().as_ref(), &bid_id.to_le_bytes()], bump)]
+    pub bid: Account<'info, Bid>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(bid_id: u64)]
+pub struct CancelBidUsdc<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, close = bidder, seeds = [RFQ_BID_SEED, desk.key().as_ref(), bidder.key().as_ref(), &bid_id.to_le_bytes()], bump)]
+    pub bid: Account<'info, Bid>,
+    #[account(constraint = payment_mint.key() == bid.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), bid.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_payment_ata.mint == bid.payment_mint @ OtcError::BadState, constraint = bidder_payment_ata.owner == bidder.key() @ OtcError::BadState)]
+    pub bidder_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
     pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawConsignment<'info> {
-    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState)]
+#[instruction(consignment_id: u64)]
+pub struct HitBidSol<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump, has_one = consigner)]
     pub consignment: Account<'info, Consignment>,
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == consignment.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, close = bidder, constraint = bid.desk == desk.key() @ OtcError::BadState)]
+    pub bid: Account<'info, Bid>,
+    /// CHECK: Bid owner, receives unused escrow and the bid account's rent
+    #[account(mut, constraint = bidder.key() == bid.bidder @ OtcError::BadState)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consigner.key().as_ref(), NATIVE_SOL.as_ref()], bump)]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+    /// Agent account for receiving SOL commission (optional - only needed if commission > 0)
+    /// CHECK: This is the agent's wallet address, we're just sending SOL to it
+    #[account(mut)]
+    pub agent: Option<AccountInfo<'info>>,
+    #[account(
+        init,
+        payer = consigner,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    #[account(mut)]
+    pub consigner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(consignment_id: u64)]
+pub struct HitBidUsdc<'info> {
+    #[account(mut)]
     pub desk: Account<'info, Desk>,
-    pub token_mint: InterfaceAccount<'info, Mint>,
-    #[account(constraint = desk_signer.key() == desk.key() @ OtcError::NotOwner)]
-    pub desk_signer: Signer<'info>,
+    #[account(mut, seeds = [CONSIGNMENT_SEED, desk.key().as_ref(), &consignment_id.to_le_bytes()], bump, has_one = consigner)]
+    pub consignment: Account<'info, Consignment>,
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == consignment.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(mut, close = bidder, constraint = bid.desk == desk.key() @ OtcError::BadState)]
+    pub bid: Account<'info, Bid>,
+    /// CHECK: Bid owner, receives the bid account's rent
+    #[account(mut, constraint = bidder.key() == bid.bidder @ OtcError::BadState)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(constraint = payment_mint.key() == bid.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), bid.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = bidder_payment_ata.mint == bid.payment_mint @ OtcError::BadState, constraint = bidder_payment_ata.owner == bid.bidder @ OtcError::BadState)]
+    pub bidder_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Agent payment-mint account for receiving commission (optional - only needed if commission > 0)
+    #[account(mut, constraint = agent_payment_ata.mint == bid.payment_mint @ OtcError::BadState)]
+    pub agent_payment_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consigner.key().as_ref(), bid.payment_mint.as_ref()], bump)]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = consigner,
+        space = 8 + Offer::SIZE,
+        seeds = [OFFER_SEED, desk.key().as_ref(), &desk.next_offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
     #[account(mut)]
     pub consigner: Signer<'info>,
-    #[account(mut, constraint = desk_token_treasury.mint == consignment.token_mint @ OtcError::BadState, constraint = desk_token_treasury.owner == desk.key() @ OtcError::BadState)]
-    pub desk_token_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = consigner_token_ata.mint == consignment.token_mint @ OtcError::BadState, constraint = consigner_token_ata.owner == consigner.key() @ OtcError::BadState)]
-    pub consigner_token_ata: InterfaceAccount<'info, TokenAccount>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceedsUsdc<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    pub consigner: Signer<'info>,
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), payment_mint.key().as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consigner.key().as_ref(), payment_mint.key().as_ref()], bump)]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = consigner_payment_ata.mint == payment_mint.key() @ OtcError::BadState, constraint = consigner_payment_ata.owner == consigner.key() @ OtcError::BadState)]
+    pub consigner_payment_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceedsSol<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub consigner: Signer<'info>,
+    #[account(mut, seeds = [PROCEEDS_SEED, desk.key().as_ref(), consigner.key().as_ref(), NATIVE_SOL.as_ref()], bump)]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+}
+
+#[derive(Accounts)]
+#[instruction(payment_mint: Pubkey)]
+pub struct OpenConsignerProceeds<'info> {
+    pub desk: Account<'info, Desk>,
+    /// CHECK: Only used as a seed; proceeds can only be withdrawn by this wallet
+    pub consigner: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ConsignerProceeds::SIZE,
+        seeds = [PROCEEDS_SEED, desk.key().as_ref(), consigner.key().as_ref(), payment_mint.as_ref()],
+        bump
+    )]
+    pub consigner_proceeds: Account<'info, ConsignerProceeds>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPaymentMint<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Registrar grant (not needed when a single owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(constraint = desk_payment_treasury.mint == payment_mint.key() @ OtcError::BadState, constraint = desk_payment_treasury.owner == desk.treasury_authority @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PaymentRegistry::SIZE,
+        seeds = [PAYMENT_SEED, desk.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaymentMintActive<'info> {
+    pub desk: Account<'info, Desk>,
+    pub authority: Signer<'info>,
+    /// Registrar grant (not needed when a single owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
+    #[account(mut, constraint = payment_registry.desk == desk.key() @ OtcError::BadState)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePaymentPriceFromPyth<'info> {
+    pub desk: Account<'info, Desk>,
+    #[account(mut, constraint = payment_registry.desk == desk.key() @ OtcError::BadState)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    pub price_feed: Account<'info, PriceUpdateV2>,
 }
 
 #[derive(Accounts)]
 pub struct WithdrawSol<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    pub desk_signer: Signer<'info>,
     pub owner: Signer<'info>,
+    /// Approved proposal (required once the desk has a multisig owner set)
+    #[account(mut)]
+    pub proposal: Option<Account<'info, OwnerProposal>>,
+    /// Treasurer grant (single-owner mode, not needed when the owner signs)
+    pub role: Option<Account<'info, RoleGrant>>,
     /// CHECK: system account
     #[account(mut)]
     pub to: UncheckedAccount<'info>,
@@ -1529,71 +3688,396 @@ pub struct WithdrawSol<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(offer_id: u64)]
 pub struct EmergencyRefundSol<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    pub desk_signer: Signer<'info>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
     pub offer: Account<'info, Offer>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
     pub caller: Signer<'info>,
     /// CHECK: payer to refund - validated against offer.payer in instruction
     #[account(mut, constraint = payer_refund.key() == offer.payer @ OtcError::BadState)]
     pub payer_refund: UncheckedAccount<'info>,
+    /// Source consignment (required when offer.consignment_id > 0) - inventory is restored on refund
+    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
     pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
 }
 
 #[derive(Accounts)]
+#[instruction(offer_id: u64)]
 pub struct EmergencyRefundUsdc<'info> {
     #[account(mut)]
     pub desk: Account<'info, Desk>,
-    pub desk_signer: Signer<'info>,
-    #[account(mut, constraint = offer.desk == desk.key() @ OtcError::BadState)]
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
     pub offer: Account<'info, Offer>,
-    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(constraint = payment_mint.key() == offer.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
+    pub caller: Signer<'info>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = payer_payment_refund.mint == offer.payment_mint @ OtcError::BadState, constraint = payer_payment_refund.owner == offer.payer @ OtcError::BadState)]
+    pub payer_payment_refund: InterfaceAccount<'info, TokenAccount>,
+    /// Source consignment (required when offer.consignment_id > 0) - inventory is restored on refund
+    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct EmergencyRefundFillSol<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(mut, seeds = [FILL_SEED, offer.key().as_ref(), offer_fill.payer.as_ref()], bump)]
+    pub offer_fill: Account<'info, OfferFill>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    pub caller: Signer<'info>,
+    /// CHECK: tranche payer to refund - validated against offer_fill.payer
+    #[account(mut, constraint = payer_refund.key() == offer_fill.payer @ OtcError::BadState)]
+    pub payer_refund: UncheckedAccount<'info>,
+    /// Source consignment (required when offer.consignment_id > 0) - inventory is restored on refund
+    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
+    pub system_program: Program<'info, System>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct EmergencyRefundFillUsdc<'info> {
+    #[account(mut)]
+    pub desk: Account<'info, Desk>,
+    /// CHECK: PDA that owns the desk treasuries and signs transfers out of them
+    #[account(seeds = [TREASURY_SEED, desk.key().as_ref()], bump = desk.treasury_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [OFFER_SEED, desk.key().as_ref(), &offer_id.to_le_bytes()], bump)]
+    pub offer: Account<'info, Offer>,
+    #[account(mut, seeds = [FILL_SEED, offer.key().as_ref(), offer_fill.payer.as_ref()], bump)]
+    pub offer_fill: Account<'info, OfferFill>,
+    /// Token registry - tracks inventory for the offer's token
+    #[account(mut, constraint = token_registry.desk == desk.key() @ OtcError::BadState, constraint = token_registry.token_mint == offer.token_mint @ OtcError::BadState)]
+    pub token_registry: Account<'info, TokenRegistry>,
+    #[account(constraint = payment_mint.key() == offer.payment_mint @ OtcError::BadState)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [PAYMENT_SEED, desk.key().as_ref(), offer.payment_mint.as_ref()], bump)]
+    pub payment_registry: Account<'info, PaymentRegistry>,
     pub caller: Signer<'info>,
-    #[account(mut, constraint = desk_usdc_treasury.owner == desk.key() @ OtcError::BadState)]
-    pub desk_usdc_treasury: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut, constraint = payer_usdc_refund.owner == offer.payer @ OtcError::BadState)]
-    pub payer_usdc_refund: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = desk_payment_treasury.key() == payment_registry.treasury @ OtcError::BadState)]
+    pub desk_payment_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = payer_payment_refund.mint == offer.payment_mint @ OtcError::BadState, constraint = payer_payment_refund.owner == offer_fill.payer @ OtcError::BadState)]
+    pub payer_payment_refund: InterfaceAccount<'info, TokenAccount>,
+    /// Source consignment (required when offer.consignment_id > 0) - inventory is restored on refund
+    #[account(mut, constraint = consignment.desk == desk.key() @ OtcError::BadState, constraint = consignment.id == offer.consignment_id @ OtcError::BadState)]
+    pub consignment: Option<Account<'info, Consignment>>,
+    /// Consigner's proceeds ledger (required when offer.consignment_id > 0)
+    #[account(mut, constraint = consigner_proceeds.desk == desk.key() @ OtcError::BadState)]
+    pub consigner_proceeds: Option<Account<'info, ConsignerProceeds>>,
     pub token_program: Interface<'info, TokenInterface>,
+    /// Approver record, only needed when the caller is acting as an approver
+    pub approver_account: Option<Account<'info, Approver>>,
+}
+
+#[account]
+pub struct Desk {
+    pub owner: Pubkey,
+    pub agent: Pubkey,
+    pub usdc_mint: Pubkey, // legacy: payments now go through PaymentRegistry
+    pub usdc_decimals: u8,
+    pub min_usd_amount_8d: u64,
+    pub quote_expiry_secs: i64,
+    pub max_price_age_secs: i64,
+    pub restrict_fulfill: bool,
+    pub next_consignment_id: u64,
+    pub next_offer_id: u64,
+    pub paused: bool,
+    pub sol_price_feed_id: [u8; 32],
+    pub sol_usd_price_8d: u64,
+    pub prices_updated_at: i64,
+    // Deprecated fields - kept for account size
+    pub token_mint: Pubkey,
+    pub token_decimals: u8,
+    pub token_deposited: u64,
+    pub token_reserved: u64,
+    pub token_price_feed_id: [u8; 32],
+    pub token_usd_price_8d: u64,
+    pub default_unlock_delay_secs: i64,
+    pub max_lockup_secs: i64,
+    pub max_token_per_order: u64,
+    pub emergency_refund_enabled: bool,
+    pub emergency_refund_deadline_secs: i64,
+    pub p2p_commission_bps: u16,
+    pub consigner_sol_owed: u64,  // Lamports in desk owed to consigners, not withdrawable by owner
+    pub treasury_authority: Pubkey, // PDA ["treasury", desk] that owns all desk token treasuries
+    pub treasury_bump: u8,
+    pub pending_owner: Pubkey, // set by transfer_owner, cleared by accept/cancel
+    pub owners: Vec<Pubkey>, // max MAX_OWNERS; multisig members once owner_threshold > 1
+    pub owner_threshold: u8,
+    pub next_proposal_id: u64,
+    pub param_timelock_secs: i64, // delay before queued parameter changes can execute
+    pub next_param_change_id: u64,
+    pub senior_approval_usd_8d: u64, // offers at or above this USD value need a senior approver (0 = off)
+    pub required_approvals: u8,
+    pub large_offer_usd_8d: u64, // offers at or above this USD value need large_offer_required_approvals (0 = off)
+    pub large_offer_required_approvals: u8,
+}
+
+impl Desk { pub const SIZE: usize = 32+32+32+1+8+8+8+1+8+8+1+32+8+8+32+1+8+8+32+8+8+8+8+1+8+2+8+32+1+32+4+(32*MAX_OWNERS)+1+8+8+8+8+1+8+1; } // +33 for treasury authority, +32 for pending owner, multisig owner set, +16 for timelock, +8 for senior approval, +10 for approval tiers
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolType { #[default] None, Raydium, Orca, PumpSwap }
+
+/// Terms of an offer carved out of a consignment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OfferTerms {
+    pub token_amount: u64,
+    pub discount_bps: u16,
+    pub payment_mint: Pubkey,
+    pub lockup_secs: i64,
+    pub agent_commission_bps: u16,
+    pub vesting_cliff_secs: i64,
+    pub vesting_duration_secs: i64,
+    pub vesting_period_secs: i64,
+    pub quote_lifetime_secs: i64, // 0 = longest allowed by desk and consignment
+}
+
+/// Quote signed off-chain by desk.agent; the signed message is its borsh encoding
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct AgentQuote {
+    pub desk: Pubkey,
+    pub consignment_id: u64,
+    pub beneficiary: Pubkey,
+    pub terms: OfferTerms,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+/// Redeemed agent quote nonce
+/// PDA: ["quote_nonce", desk, nonce]
+#[account]
+pub struct QuoteNonce {
+    pub desk: Pubkey,
+    pub nonce: u64,
+    pub offer: Pubkey,
+    pub used_at: i64,
+}
+
+impl QuoteNonce { pub const SIZE: usize = 32+8+32+8; }
+
+/// One payer's tranche of a syndicated offer
+/// PDA: ["fill", offer, payer]
+#[account]
+pub struct OfferFill {
+    pub offer: Pubkey,
+    pub payer: Pubkey,
+    pub token_amount: u64, // token entitlement, delivered pro rata to the offer's vesting
+    pub amount_paid: u64, // payment mint units (lamports for NATIVE_SOL)
+    pub consigner_proceeds: u64,
+    pub claimed_amount: u64,
+}
+
+impl OfferFill { pub const SIZE: usize = 32+32+8+8+8+8; }
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RevealedBid {
+    pub bidder: Pubkey,
+    pub discount_bps: u16,
+    pub token_amount: u64,
+}
+
+impl RevealedBid { pub const SIZE: usize = 32+2+8; }
+
+/// Commit-reveal auction of a sealed-bid consignment's block, closed once it finishes
+/// PDA: ["auction", consignment]
+#[account]
+pub struct SealedBidAuction {
+    pub consignment: Pubkey,
+    pub token_amount: u64, // block taken out of consignment.remaining_amount while the auction runs
+    pub bid_end: i64,
+    pub reveal_end: i64,
+    pub deposit_amount: u64, // desk.usdc_mint units escrowed per bid
+    pub commit_count: u8,
+    pub released_count: u8, // bids settled or refunded
+    pub awarded_amount: u64,
+    pub finished: bool,
+    pub revealed: Vec<RevealedBid>, // in reveal order, max MAX_AUCTION_BIDS
+}
+
+impl SealedBidAuction { pub const SIZE: usize = 32+8+8+8+8+1+1+8+1+4+(RevealedBid::SIZE*MAX_AUCTION_BIDS); }
+
+/// One bidder's commitment in a sealed-bid auction, closed when its deposit is released
+/// PDA: ["bid", auction, bidder]
+#[account]
+pub struct SealedBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub commitment: [u8; 32],
+    pub deposit: u64,
+    pub revealed: bool,
+}
+
+impl SealedBid { pub const SIZE: usize = 32+32+32+8+1; }
+
+/// Buyer-side RFQ bid with escrowed payment, closed when hit or cancelled
+/// PDA: ["rfq_bid", desk, bidder, id]
+#[account]
+pub struct Bid {
+    pub desk: Pubkey,
+    pub bidder: Pubkey,
+    pub id: u64,
+    pub token_mint: Pubkey,
+    pub payment_mint: Pubkey, // NATIVE_SOL escrows lamports in this account, SPL mints escrow in the desk treasury
+    pub token_amount: u64,
+    pub max_discount_bps: u16,
+    pub max_lockup_secs: i64, // tolerance for lockup plus vesting duration
+    pub escrow_amount: u64,
+    pub created_at: i64,
+}
+
+impl Bid { pub const SIZE: usize = 32+32+8+32+32+8+2+8+8+8; }
+
+/// Named desk roles; the owner implicitly holds all of them in single-owner mode
+/// Approvers have their own Approver accounts with USD limits, managed by ApproverAdmin
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role { Pauser, PriceSetter, Treasurer, Registrar, ApproverAdmin }
+
+/// Offer approver with per-offer and daily USD limits
+/// PDA: ["approver", desk, approver]
+#[account]
+pub struct Approver {
+    pub desk: Pubkey,
+    pub approver: Pubkey,
+    pub max_usd_per_offer_8d: u64,
+    pub daily_limit_usd_8d: u64,
+    pub is_senior: bool,
+    pub day_index: i64, // unix day of day_used_usd_8d
+    pub day_used_usd_8d: u64,
+}
+
+impl Approver { pub const SIZE: usize = 32+32+8+8+1+8+8; }
+
+/// One approver's sign-off on an offer
+/// PDA: ["approval", offer, approver]
+#[account]
+pub struct OfferApproval {
+    pub offer: Pubkey,
+    pub approver: Pubkey,
+    pub approved_at: i64,
+}
+
+impl OfferApproval { pub const SIZE: usize = 32+32+8; }
+
+/// Grant of one role to one holder
+/// PDA: ["role", desk, role, holder]
+#[account]
+pub struct RoleGrant {
+    pub desk: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_at: i64,
+}
+
+impl RoleGrant { pub const SIZE: usize = 32+1+32+8; }
+
+/// Sensitive owner action gated by the multisig; withdrawals bind their destination account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum ProposalAction {
+    WithdrawTokens { token_mint: Pubkey, amount: u64, to: Pubkey },
+    WithdrawUsdc { payment_mint: Pubkey, amount: u64, to: Pubkey },
+    WithdrawSol { lamports: u64, to: Pubkey },
+    SetAgent { new_agent: Pubkey },
+    TransferOwner { new_owner: Pubkey },
+    SetEmergencyRefund { enabled: bool, deadline_secs: i64 },
+    SetOwners { owners: Vec<Pubkey>, threshold: u8 },
+    SetApprover { approver: Pubkey, max_usd_per_offer_8d: u64, daily_limit_usd_8d: u64, is_senior: bool },
+    RemoveApprover { approver: Pubkey },
+    GrantRole { role: Role, holder: Pubkey },
+    RevokeRole { role: Role, holder: Pubkey },
+    QueueParamChange { change: ParamChange },
+    ExecuteParamChange { change_id: u64 },
+    CancelParamChange { change_id: u64 },
+    SetParamTimelock { delay_secs: i64 },
+    CancelOwnershipTransfer,
+    SetRestrictFulfill { enabled: bool },
+    SetRequiredApprovals { required_approvals: u8, large_offer_usd_8d: u64, large_offer_required_approvals: u8 },
+    SetSeniorApprovalUsd { usd_8d: u64 },
+}
+
+impl ProposalAction { pub const MAX_SIZE: usize = 1+4+(32*MAX_OWNERS)+1; } // largest variant is SetOwners
+
+/// Multisig proposal executed by calling the gated instruction with matching arguments
+/// PDA: ["proposal", desk, id]
+#[account]
+pub struct OwnerProposal {
+    pub desk: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub approvals: Vec<Pubkey>, // distinct owner-set members, max MAX_OWNERS
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub executed: bool,
+    pub cancelled: bool, // withdrawn by the proposer; can no longer be approved or executed
 }
 
-#[account]
-pub struct Desk {
-    pub owner: Pubkey,
-    pub agent: Pubkey,
-    pub usdc_mint: Pubkey,
-    pub usdc_decimals: u8,
-    pub min_usd_amount_8d: u64,
-    pub quote_expiry_secs: i64,
-    pub max_price_age_secs: i64,
-    pub restrict_fulfill: bool,
-    pub approvers: Vec<Pubkey>, // max 32
-    pub next_consignment_id: u64,
-    pub next_offer_id: u64,
-    pub paused: bool,
-    pub sol_price_feed_id: [u8; 32],
-    pub sol_usd_price_8d: u64,
-    pub prices_updated_at: i64,
-    // Deprecated fields - kept for account size
-    pub token_mint: Pubkey,
-    pub token_decimals: u8,
-    pub token_deposited: u64,
-    pub token_reserved: u64,
-    pub token_price_feed_id: [u8; 32],
-    pub token_usd_price_8d: u64,
-    pub default_unlock_delay_secs: i64,
-    pub max_lockup_secs: i64,
-    pub max_token_per_order: u64,
-    pub emergency_refund_enabled: bool,
-    pub emergency_refund_deadline_secs: i64,
-    pub p2p_commission_bps: u16,
+impl OwnerProposal { pub const SIZE: usize = 32+8+32+ProposalAction::MAX_SIZE+4+(32*MAX_OWNERS)+8+8+1+1; }
+
+/// Economic or oracle parameter change subject to the desk timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum ParamChange {
+    SetLimits { min_usd_amount_8d: u64, max_token_per_order: u64, quote_expiry_secs: i64, default_unlock_delay_secs: i64, max_lockup_secs: i64 },
+    SetP2pCommission { bps: u16 },
+    ConfigurePoolOracle { token_registry: Pubkey, min_liquidity: u64, max_twap_deviation_bps: u16, min_update_interval_secs: i64 },
+    SetTokenOracleFeed { token_registry: Pubkey, feed_id: [u8; 32] },
+    SetParamTimelock { delay_secs: i64 },
+    SetPythFeeds { token_feed_id: [u8; 32], sol_feed_id: [u8; 32] },
+    SetTokenPoolConfig { token_registry: Pubkey, pool_address: Pubkey, pool_type: u8 }, // 0=None, 1=Raydium, 2=Orca, 3=PumpSwap
+    SetMaxPriceAge { max_age_secs: i64 },
 }
 
-impl Desk { pub const SIZE: usize = 32+32+32+1+8+8+8+1+4+(32*32)+8+8+1+32+8+8+32+1+8+8+32+8+8+8+8+1+8+2; } // +2 for p2p_commission_bps
+impl ParamChange { pub const MAX_SIZE: usize = 1+32+32+1; } // largest variant is SetTokenPoolConfig
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
-pub enum PoolType { #[default] None, Raydium, Orca, PumpSwap }
+/// Queued parameter change awaiting its timelock
+/// PDA: ["param_change", desk, id]
+#[account]
+pub struct PendingParamChange {
+    pub desk: Pubkey,
+    pub id: u64,
+    pub change: ParamChange,
+    pub queued_at: i64,
+    pub eta: i64,
+}
+
+impl PendingParamChange { pub const SIZE: usize = 32+8+ParamChange::MAX_SIZE+8+8; }
 
 #[account]
 pub struct TokenRegistry {
@@ -1614,13 +4098,19 @@ pub struct TokenRegistry {
     pub twap_last_price: u64,
     pub max_twap_deviation_bps: u16,
     pub min_update_interval_secs: i64,
+    // Treasury inventory split for this token (sums to at most the treasury balance)
+    pub desk_owned_amount: u64, // deposited via deposit_tokens, withdrawable by owner
+    pub consigned_amount: u64,  // locked by consignments, including unpaid offers carved from them
+    pub reserved_amount: u64,   // held for open direct offers and paid offers awaiting claim
 }
 
 impl TokenRegistry { 
     // 32+32+1+32+32+1+1+8+8+32 = 179 (original)
     // + 8 (min_liquidity) + 16 (twap_cumulative) + 8 (twap_last_ts) + 8 (twap_last_price) + 2 (max_twap_dev) + 8 (min_update) = 50
     // Total = 229
-    pub const SIZE: usize = 32+32+1+32+32+1+1+8+8+32+8+16+8+8+2+8;
+    // + 8 (desk_owned) + 8 (consigned) + 8 (reserved) = 24
+    // Total = 253
+    pub const SIZE: usize = 32+32+1+32+32+1+1+8+8+32+8+16+8+8+2+8+8+8+8;
 }
 
 #[account]
@@ -1646,9 +4136,50 @@ pub struct Consignment {
     pub max_time_to_execute_secs: i64,
     pub is_active: bool,
     pub created_at: i64,
+    // Vesting: fixed schedule for P2P, upper bounds (cliff, duration) for negotiable
+    pub vesting_cliff_days: u32,
+    pub vesting_duration_days: u32,
+    pub vesting_period_days: u32,
+    pub max_quote_lifetime_secs: i64, // cap on offer lifetime, 0 = desk.quote_expiry_secs only
+    // Dutch auction: discount rises linearly from min_discount_bps at created_at to max_discount_bps
+    pub is_dutch_auction: bool,
+    pub auction_duration_secs: i64,
+    pub is_sealed_bid: bool, // sold only through a commit-reveal SealedBidAuction
+    pub withdrawn: bool, // consigner pulled the tokens back; cancelled offers no longer reopen it
+}
+
+impl Consignment { pub const SIZE: usize = 32+8+32+32+8+8+1+2+4+2+2+4+4+8+8+1+1+2+8+1+8+4+4+4+8+1+8+1+1; } // +12 for vesting, +8 for quote lifetime, +9 for dutch auction, +1 for sealed bid, +1 for withdrawn
+
+/// Per-consigner ledger of sale proceeds held by the desk until the consigner withdraws them
+/// PDA: ["proceeds", desk, consigner, payment_mint] (NATIVE_SOL for lamports)
+#[account]
+pub struct ConsignerProceeds {
+    pub desk: Pubkey,
+    pub consigner: Pubkey,
+    pub payment_mint: Pubkey,
+    pub owed: u64,
+}
+
+impl ConsignerProceeds { pub const SIZE: usize = 32+32+32+8; }
+
+/// SPL mint accepted as payment by a desk
+/// PDA: ["payment", desk, mint]
+#[account]
+pub struct PaymentRegistry {
+    pub desk: Pubkey,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub treasury: Pubkey, // desk treasury token account for this mint
+    pub price_feed_id: [u8; 32],
+    pub is_pegged: bool, // stablecoins priced at $1 without an oracle
+    pub usd_price_8d: u64,
+    pub prices_updated_at: i64,
+    pub is_active: bool,
+    pub consigner_owed: u64, // held in treasury for consigners, not withdrawable by owner
+    pub bid_escrow: u64, // sealed-bid deposits held for bidders, not withdrawable by owner
 }
 
-impl Consignment { pub const SIZE: usize = 32+8+32+32+8+8+1+2+4+2+2+4+4+8+8+1+1+2+8+1+8; }
+impl PaymentRegistry { pub const SIZE: usize = 32+32+1+32+32+1+8+8+1+8+8; }
 
 #[account]
 pub struct Offer {
@@ -1664,22 +4195,805 @@ pub struct Offer {
     pub unlock_time: i64,
     pub price_usd_per_token_8d: u64,
     pub max_price_deviation_bps: u16,
-    pub sol_usd_price_8d: u64,
-    pub currency: u8,
+    pub payment_usd_price_8d: u64, // USD price of the payment mint captured at creation
+    pub payment_mint: Pubkey, // NATIVE_SOL for lamports, otherwise a registered SPL mint
     pub approved: bool,
     pub paid: bool,
     pub fulfilled: bool,
     pub cancelled: bool,
-    pub payer: Pubkey,
+    pub payer: Pubkey, // unset for syndicated offers (fill_count > 0); each payer is on its OfferFill
     pub amount_paid: u64,
     pub agent_commission_bps: u16, // p2p_commission_bps for P2P (default 0.25%), 25-150 for negotiated deals
+    pub consigner_proceeds: u64, // Amount credited to the consigner at fulfillment (payment currency units)
+    // Optional vesting after unlock_time; vesting_duration_secs == 0 releases everything at unlock_time
+    pub vesting_start: i64,
+    pub vesting_cliff_secs: i64,
+    pub vesting_duration_secs: i64,
+    pub vesting_period_secs: i64,
+    pub claimed_amount: u64,
+    pub approval_count: u8, // distinct approvals recorded by approve_offer
+    pub expires_at: i64, // fixed at creation so later set_limits calls don't move it
+    pub filled_amount: u64, // tokens paid for so far, by one fulfil or several fill tranches
+    pub fill_count: u32, // number of OfferFill records; claims go through claim_fill when > 0
+    pub award_deposit: u64, // sealed-bid deposit held in bid_escrow toward this award's payment
+    pub syndicated: bool, // beneficiary opted in to fills from other payers; otherwise only the beneficiary fills
 }
 
-impl Offer { pub const SIZE: usize = 32+8+32+1+8+32+8+2+8+8+8+2+8+1+1+1+1+1+32+8+2; } // +2 for agent_commission_bps
+impl Offer { pub const SIZE: usize = 32+8+32+1+8+32+8+2+8+8+8+2+8+32+1+1+1+1+32+8+2+8+8+8+8+8+8+1+8+8+4+8+1; } // +40 for vesting, +1 for approval count, +8 for expiry, +12 for fills, +8 for award deposit, +1 for syndication
 
 fn only_owner(desk: &Desk, who: &Pubkey) -> Result<()> { require!(*who == desk.owner, OtcError::NotOwner); Ok(()) }
-fn must_be_approver(desk: &Desk, who: &Pubkey) -> Result<()> { require!((*who == desk.agent) || desk.approvers.contains(who), OtcError::NotApprover); Ok(()) }
+
+fn validate_param_change(change: &ParamChange) -> Result<()> {
+    match change {
+        ParamChange::SetLimits { min_usd_amount_8d, max_token_per_order, quote_expiry_secs, default_unlock_delay_secs, max_lockup_secs } => {
+            require!(*min_usd_amount_8d > 0, OtcError::AmountRange);
+            require!(*max_token_per_order > 0, OtcError::AmountRange);
+            require!(*quote_expiry_secs >= 60, OtcError::AmountRange); // Minimum 60 seconds to prevent race conditions
+            require!(*max_lockup_secs >= 0, OtcError::AmountRange);
+            require!(*default_unlock_delay_secs >= 0 && default_unlock_delay_secs <= max_lockup_secs, OtcError::AmountRange);
+        }
+        ParamChange::SetP2pCommission { bps } => require!(*bps <= 500, OtcError::CommissionRange), // Max 5% for P2P
+        ParamChange::ConfigurePoolOracle { max_twap_deviation_bps, min_update_interval_secs, .. } => {
+            require!(*min_update_interval_secs >= 30, OtcError::AmountRange); // Minimum 30 seconds
+            require!(*max_twap_deviation_bps <= 5000, OtcError::AmountRange); // Max 50% deviation
+        }
+        ParamChange::SetTokenOracleFeed { .. } | ParamChange::SetPythFeeds { .. } | ParamChange::SetTokenPoolConfig { .. } => {}
+        ParamChange::SetParamTimelock { delay_secs } => require!(*delay_secs >= 0 && *delay_secs <= MAX_PARAM_TIMELOCK_SECS, OtcError::AmountRange),
+        ParamChange::SetMaxPriceAge { max_age_secs } => require!(*max_age_secs >= 0, OtcError::AmountRange),
+    }
+    Ok(())
+}
+
+fn apply_desk_param_change(desk: &mut Desk, change: &ParamChange) -> Result<()> {
+    validate_param_change(change)?;
+    match *change {
+        ParamChange::SetLimits { min_usd_amount_8d, max_token_per_order, quote_expiry_secs, default_unlock_delay_secs, max_lockup_secs } => {
+            desk.min_usd_amount_8d = min_usd_amount_8d;
+            desk.max_token_per_order = max_token_per_order;
+            desk.quote_expiry_secs = quote_expiry_secs;
+            desk.default_unlock_delay_secs = default_unlock_delay_secs;
+            desk.max_lockup_secs = max_lockup_secs;
+            emit!(LimitsUpdated { min_usd_amount_8d, max_token_per_order, quote_expiry_secs, default_unlock_delay_secs, max_lockup_secs });
+        }
+        ParamChange::SetP2pCommission { bps } => desk.p2p_commission_bps = bps,
+        ParamChange::SetParamTimelock { delay_secs } => desk.param_timelock_secs = delay_secs,
+        ParamChange::SetMaxPriceAge { max_age_secs } => desk.max_price_age_secs = max_age_secs,
+        ParamChange::SetPythFeeds { token_feed_id, sol_feed_id } => {
+            desk.token_price_feed_id = token_feed_id;
+            desk.sol_price_feed_id = sol_feed_id;
+        }
+        ParamChange::ConfigurePoolOracle { .. } | ParamChange::SetTokenOracleFeed { .. } | ParamChange::SetTokenPoolConfig { .. } => return err!(OtcError::BadState),
+    }
+    Ok(())
+}
+
+fn pool_type_from_u8(pool_type: u8) -> PoolType {
+    match pool_type {
+        1 => PoolType::Raydium,
+        2 => PoolType::Orca,
+        3 => PoolType::PumpSwap,
+        _ => PoolType::None,
+    }
+}
+
+fn apply_registry_param_change(registry: &mut TokenRegistry, change: &ParamChange) -> Result<()> {
+    validate_param_change(change)?;
+    match *change {
+        ParamChange::ConfigurePoolOracle { min_liquidity, max_twap_deviation_bps, min_update_interval_secs, .. } => {
+            registry.min_liquidity = min_liquidity;
+            registry.max_twap_deviation_bps = max_twap_deviation_bps;
+            registry.min_update_interval_secs = min_update_interval_secs;
+        }
+        ParamChange::SetTokenOracleFeed { feed_id, .. } => registry.price_feed_id = feed_id,
+        ParamChange::SetTokenPoolConfig { pool_address, pool_type, .. } => {
+            registry.pool_address = pool_address;
+            registry.pool_type = pool_type_from_u8(pool_type);
+        }
+        _ => return err!(OtcError::BadState),
+    }
+    Ok(())
+}
+
+/// Gate a withdrawal: owner or Treasurer in single-owner mode, otherwise an approved proposal
+fn authorize_treasury_action(desk: &Account<Desk>, who: &Pubkey, role: &Option<Account<RoleGrant>>, proposal: &mut Option<Account<OwnerProposal>>, action: ProposalAction) -> Result<()> {
+    if desk.owner_threshold <= 1 {
+        return require_role(desk, who, role, Role::Treasurer);
+    }
+    authorize_owner_action(desk, who, proposal, action)
+}
+
+/// Gate an approval-policy change: owner or ApproverAdmin in single-owner mode, otherwise an approved proposal
+fn authorize_approver_admin_action(desk: &Account<Desk>, who: &Pubkey, role: &Option<Account<RoleGrant>>, proposal: &mut Option<Account<OwnerProposal>>, action: ProposalAction) -> Result<()> {
+    if desk.owner_threshold <= 1 {
+        return require_role(desk, who, role, Role::ApproverAdmin);
+    }
+    authorize_owner_action(desk, who, proposal, action)
+}
+
+/// Gate a sensitive owner action: the owner alone in single-owner mode,
+/// otherwise an owner-set member consuming an unexpired proposal for exactly this action
+fn authorize_owner_action(desk: &Account<Desk>, who: &Pubkey, proposal: &mut Option<Account<OwnerProposal>>, action: ProposalAction) -> Result<()> {
+    if desk.owner_threshold <= 1 {
+        return only_owner(desk, who);
+    }
+    require!(desk.owners.contains(who), OtcError::NotOwner);
+    let proposal = proposal.as_mut().ok_or(OtcError::ProposalRequired)?;
+    require!(proposal.desk == desk.key() && !proposal.executed && !proposal.cancelled, OtcError::BadState);
+    require!(Clock::get()?.unix_timestamp <= proposal.expires_at, OtcError::Expired);
+    require!(proposal.action == action, OtcError::ProposalMismatch);
+    // Approvals from members removed since the proposal was created no longer count
+    let approvals = proposal.approvals.iter().filter(|a| desk.owners.contains(a)).count();
+    require!(approvals >= desk.owner_threshold as usize, OtcError::NotApproved);
+    proposal.executed = true;
+    emit!(ProposalExecuted { proposal: proposal.key(), executor: *who });
+    Ok(())
+}
+/// Expiry for a new offer: the requested lifetime (0 = longest allowed) clamped to
+/// MIN_QUOTE_LIFETIME_SECS and the lesser of desk.quote_expiry_secs and the consignment cap
+fn offer_expires_at(desk: &Desk, consignment_cap_secs: i64, requested_secs: i64, now: i64) -> Result<i64> {
+    require!(requested_secs >= 0, OtcError::AmountRange);
+    let mut max_secs = desk.quote_expiry_secs;
+    if consignment_cap_secs > 0 {
+        max_secs = max_secs.min(consignment_cap_secs);
+    }
+    let lifetime = if requested_secs == 0 { max_secs } else { requested_secs.max(MIN_QUOTE_LIFETIME_SECS).min(max_secs) };
+    Ok(now.checked_add(lifetime).ok_or(OtcError::Overflow)?)
+}
+
+/// Check that the previous instruction is an ed25519 verification of `message` signed by `agent`
+fn verify_agent_signature(instructions: &AccountInfo, agent: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, OtcError::InvalidQuoteSignature);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions)?;
+    require!(ix.program_id == ed25519_program::ID, OtcError::InvalidQuoteSignature);
+    // Layout: [num_signatures u8, padding u8, then one 14-byte offsets record]
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, OtcError::InvalidQuoteSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    // Signature, key and message must live in the ed25519 instruction itself (index u16::MAX)
+    require!(read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX, OtcError::InvalidQuoteSignature);
+    let pubkey_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let signer = data.get(pubkey_offset..pubkey_offset + 32).ok_or(OtcError::InvalidQuoteSignature)?;
+    let signed = data.get(message_offset..message_offset + message_size).ok_or(OtcError::InvalidQuoteSignature)?;
+    require!(signer == agent.as_ref() && signed == message, OtcError::InvalidQuoteSignature);
+    Ok(())
+}
+
+fn required_approvals_for(desk: &Desk, usd_8d: u64) -> u8 {
+    if desk.large_offer_usd_8d > 0 && usd_8d >= desk.large_offer_usd_8d {
+        desk.large_offer_required_approvals
+    } else {
+        desk.required_approvals
+    }
+}
+
+fn is_approver(desk_key: Pubkey, who: &Pubkey, approver_account: &Option<Account<Approver>>) -> bool {
+    approver_account.as_ref().is_some_and(|a| a.desk == desk_key && a.approver == *who)
+}
+
+/// A single owner holds every role; with a multisig owner set everyone, the owner included,
+/// needs a matching RoleGrant so one key cannot act alone
+fn require_role(desk: &Account<Desk>, who: &Pubkey, grant: &Option<Account<RoleGrant>>, role: Role) -> Result<()> {
+    if desk.owner_threshold <= 1 && *who == desk.owner {
+        return Ok(());
+    }
+    let grant = grant.as_ref().ok_or(OtcError::MissingRole)?;
+    require!(grant.desk == desk.key() && grant.holder == *who && grant.role == role, OtcError::MissingRole);
+    Ok(())
+}
 fn pow10(exp: u32) -> u128 { 10u128.pow(exp) }
+
+fn validate_vesting(cliff_secs: i64, duration_secs: i64, period_secs: i64) -> Result<()> {
+    require!(cliff_secs >= 0 && duration_secs >= 0 && period_secs >= 0, OtcError::InvalidVesting);
+    if duration_secs == 0 {
+        require!(cliff_secs == 0 && period_secs == 0, OtcError::InvalidVesting);
+    } else {
+        require!(period_secs > 0 && period_secs <= duration_secs && cliff_secs <= duration_secs, OtcError::InvalidVesting);
+    }
+    Ok(())
+}
+
+/// Tokens vested at `now`: nothing before start + cliff, then linear in whole periods until start + duration
+fn vested_amount(offer: &Offer, now: i64) -> Result<u64> {
+    if offer.vesting_duration_secs == 0 {
+        return Ok(if now >= offer.unlock_time { offer.token_amount } else { 0 });
+    }
+    let elapsed = now.saturating_sub(offer.vesting_start);
+    if elapsed < offer.vesting_cliff_secs {
+        return Ok(0);
+    }
+    if elapsed >= offer.vesting_duration_secs {
+        return Ok(offer.token_amount);
+    }
+    let vested_secs = elapsed / offer.vesting_period_secs * offer.vesting_period_secs;
+    // SAFETY: 0 <= vested_secs < vesting_duration_secs here
+    #[allow(clippy::cast_sign_loss)]
+    let vested = mul_div_u128(offer.token_amount as u128, vested_secs as u128, offer.vesting_duration_secs as u128)?;
+    safe_u128_to_u64(vested)
+}
+
+/// Current discount of a Dutch auction consignment, capped at max_discount_bps once the auction ends
+fn dutch_auction_discount_bps(consignment: &Consignment, now: i64) -> Result<u16> {
+    let elapsed = now.saturating_sub(consignment.created_at).clamp(0, consignment.auction_duration_secs);
+    let range = consignment.max_discount_bps.checked_sub(consignment.min_discount_bps).ok_or(OtcError::Discount)?;
+    // SAFETY: 0 <= elapsed <= auction_duration_secs here
+    #[allow(clippy::cast_sign_loss)]
+    let step = mul_div_u128(range as u128, elapsed as u128, consignment.auction_duration_secs as u128)?;
+    let step = u16::try_from(step).map_err(|_| OtcError::Overflow)?;
+    Ok(consignment.min_discount_bps.checked_add(step).ok_or(OtcError::Overflow)?)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn init_bid(
+    bid: &mut Bid,
+    desk_key: Pubkey,
+    bidder: Pubkey,
+    bid_id: u64,
+    registry: &TokenRegistry,
+    payment_mint: Pubkey,
+    token_amount: u64,
+    max_discount_bps: u16,
+    max_lockup_secs: i64,
+    escrow_amount: u64,
+) -> Result<()> {
+    require!(registry.is_active, OtcError::BadState);
+    require!(token_amount > 0 && escrow_amount > 0, OtcError::AmountRange);
+    require!(max_discount_bps <= 10000, OtcError::Discount);
+    require!(max_lockup_secs >= 0, OtcError::LockupTooLong);
+    bid.desk = desk_key;
+    bid.bidder = bidder;
+    bid.id = bid_id;
+    bid.token_mint = registry.token_mint;
+    bid.payment_mint = payment_mint;
+    bid.token_amount = token_amount;
+    bid.max_discount_bps = max_discount_bps;
+    bid.max_lockup_secs = max_lockup_secs;
+    bid.escrow_amount = escrow_amount;
+    bid.created_at = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+fn emit_bid_placed(bid: &Bid, bid_key: Pubkey) {
+    emit!(BidPlaced {
+        desk: bid.desk,
+        bid: bid_key,
+        bidder: bid.bidder,
+        token_mint: bid.token_mint,
+        payment_mint: bid.payment_mint,
+        token_amount: bid.token_amount,
+        max_discount_bps: bid.max_discount_bps,
+        max_lockup_secs: bid.max_lockup_secs,
+        escrow_amount: bid.escrow_amount,
+    });
+}
+
+/// Check a consigner's terms against a bid and the consignment, then record a paid offer for the bidder
+/// Returns the payment taken from escrow and the agent commission included in it
+#[allow(clippy::too_many_arguments)]
+fn open_offer_for_bid<'info>(
+    desk: &mut Account<'info, Desk>,
+    consignment: &mut Account<'info, Consignment>,
+    registry: &mut Account<'info, TokenRegistry>,
+    bid: &Account<'info, Bid>,
+    offer: &mut Account<'info, Offer>,
+    discount_bps: u16,
+    lockup_secs: i64,
+    payment_usd_price_8d: u64,
+    payment_decimals: u8,
+    now: i64,
+) -> Result<(u64, u64)> {
+    let desk_key = desk.key();
+    require!(!desk.paused, OtcError::Paused);
+    require!(consignment.is_active && !consignment.is_sealed_bid, OtcError::BadState);
+    require!(!consignment_expired(consignment, now)?, OtcError::Expired);
+    require!(bid.token_mint == consignment.token_mint, OtcError::BadState);
+    let token_amount = bid.token_amount;
+    require!(token_amount >= consignment.min_deal_amount && token_amount <= consignment.max_deal_amount, OtcError::AmountRange);
+    require!(token_amount <= consignment.remaining_amount, OtcError::InsuffInv);
+    if !consignment.is_fractionalized {
+        require!(token_amount == consignment.remaining_amount, OtcError::AmountRange);
+    }
+
+    // The consignment's own terms apply; the bid only caps discount and lock-up
+    let (vesting_cliff_secs, vesting_duration_secs, vesting_period_secs) = if consignment.is_negotiable {
+        require!(discount_bps >= consignment.min_discount_bps && discount_bps <= consignment.max_discount_bps, OtcError::Discount);
+        require!(lockup_secs >= consignment.min_lockup_days as i64 * 86400 && lockup_secs <= consignment.max_lockup_days as i64 * 86400, OtcError::LockupTooLong);
+        (0, 0, 0)
+    } else {
+        let expected_discount = if consignment.is_dutch_auction { dutch_auction_discount_bps(consignment, now)? } else { consignment.fixed_discount_bps };
+        require!(discount_bps == expected_discount, OtcError::Discount);
+        require!(lockup_secs == consignment.fixed_lockup_days as i64 * 86400, OtcError::LockupTooLong);
+        (consignment.vesting_cliff_days as i64 * 86400, consignment.vesting_duration_days as i64 * 86400, consignment.vesting_period_days as i64 * 86400)
+    };
+    require!(discount_bps <= bid.max_discount_bps, OtcError::Discount);
+    require!(lockup_secs.checked_add(vesting_duration_secs).ok_or(OtcError::Overflow)? <= bid.max_lockup_secs, OtcError::LockupTooLong);
+
+    require!(registry.token_usd_price_8d > 0, OtcError::NoPrice);
+    require!(now.saturating_sub(registry.prices_updated_at) <= desk.max_price_age_secs, OtcError::StalePrice);
+    require!(payment_usd_price_8d > 0, OtcError::NoPrice);
+    let usd_8d = calc_discounted_usd(token_amount, registry.token_usd_price_8d, registry.decimals, discount_bps)?;
+    require!(usd_8d >= desk.min_usd_amount_8d, OtcError::MinUsd);
+    let payment_amount = safe_u128_to_u64(mul_div_ceil_u128(usd_8d as u128, pow10(payment_decimals as u32), payment_usd_price_8d as u128)?)?;
+    require!(payment_amount <= bid.escrow_amount, OtcError::SlippageExceeded);
+    let commission_bps = desk.p2p_commission_bps;
+    let commission_usd_8d = usd_8d.checked_mul(commission_bps as u64).ok_or(OtcError::Overflow)?.checked_div(10_000).ok_or(OtcError::Overflow)?;
+    let commission = safe_u128_to_u64(mul_div_u128(commission_usd_8d as u128, pow10(payment_decimals as u32), payment_usd_price_8d as u128)?)?;
+
+    consignment.remaining_amount = consignment.remaining_amount.checked_sub(token_amount).ok_or(OtcError::Overflow)?;
+    if consignment.remaining_amount == 0 {
+        consignment.is_active = false;
+    }
+
+    let offer_id = desk.next_offer_id;
+    desk.next_offer_id = offer_id.checked_add(1).ok_or(OtcError::Overflow)?;
+    let offer_key = offer.key();
+    offer.desk = desk_key;
+    offer.consignment_id = consignment.id;
+    offer.token_mint = consignment.token_mint;
+    offer.token_decimals = registry.decimals;
+    offer.id = offer_id;
+    offer.beneficiary = bid.bidder;
+    offer.token_amount = token_amount;
+    offer.discount_bps = discount_bps;
+    offer.created_at = now;
+    offer.unlock_time = now.checked_add(lockup_secs).ok_or(OtcError::Overflow)?;
+    offer.price_usd_per_token_8d = registry.token_usd_price_8d;
+    offer.max_price_deviation_bps = consignment.max_price_volatility_bps;
+    offer.payment_usd_price_8d = payment_usd_price_8d;
+    offer.payment_mint = bid.payment_mint;
+    offer.approved = true;
+    offer.paid = true;
+    offer.fulfilled = false;
+    offer.cancelled = false;
+    offer.payer = bid.bidder;
+    offer.amount_paid = payment_amount;
+    offer.agent_commission_bps = commission_bps;
+    offer.consigner_proceeds = payment_amount.checked_sub(commission).ok_or(OtcError::Overflow)?;
+    offer.vesting_start = offer.unlock_time;
+    offer.vesting_cliff_secs = vesting_cliff_secs;
+    offer.vesting_duration_secs = vesting_duration_secs;
+    offer.vesting_period_secs = vesting_period_secs;
+    offer.claimed_amount = 0;
+    offer.approval_count = 0;
+    offer.expires_at = offer_expires_at(desk, consignment.max_quote_lifetime_secs, 0, now)?;
+    offer.filled_amount = token_amount;
+    offer.fill_count = 0;
+    offer.syndicated = false;
+    offer.award_deposit = 0;
+    reserve_for_paid_offer(registry, offer, token_amount)?;
+
+    emit!(OfferCreated { desk: desk_key, offer: offer_key, beneficiary: bid.bidder, token_amount, discount_bps, payment_mint: bid.payment_mint });
+    emit!(OfferApproved { offer: offer_key, approver: consignment.consigner });
+    Ok((payment_amount, commission))
+}
+
+/// Commitment a sealed bid must match when revealed
+fn sealed_bid_commitment(discount_bps: u16, token_amount: u64, salt: &[u8; 32], bidder: &Pubkey) -> [u8; 32] {
+    hashv(&[&discount_bps.to_le_bytes(), &token_amount.to_le_bytes(), salt, bidder.as_ref()]).to_bytes()
+}
+
+/// Tokens awarded to a revealed bidder and their discount
+/// Revealed bids fill the block by lowest discount, ties by reveal order; a partial award
+/// smaller than min_deal_amount is dropped and the remainder goes back to the consignment
+fn sealed_bid_award(auction: &SealedBidAuction, consignment: &Consignment, bidder: &Pubkey) -> Result<(u64, u16)> {
+    let (position, bid) = auction.revealed.iter().enumerate().find(|(_, b)| b.bidder == *bidder).ok_or(OtcError::BadState)?;
+    let mut ahead: u64 = 0;
+    for (i, other) in auction.revealed.iter().enumerate() {
+        if other.discount_bps < bid.discount_bps || (other.discount_bps == bid.discount_bps && i < position) {
+            ahead = ahead.checked_add(other.token_amount).ok_or(OtcError::Overflow)?;
+        }
+    }
+    let left = auction.token_amount.saturating_sub(ahead);
+    let award = bid.token_amount.min(left);
+    if award < bid.token_amount && award < consignment.min_deal_amount {
+        return Ok((0, bid.discount_bps));
+    }
+    Ok((award, bid.discount_bps))
+}
+
+/// Return `amount` of a bid's escrowed deposit to the bidder and count the bid as released
+#[allow(clippy::too_many_arguments)]
+fn release_bid_deposit<'info>(
+    desk: &Account<'info, Desk>,
+    payment_registry: &mut Account<'info, PaymentRegistry>,
+    auction: &mut SealedBidAuction,
+    amount: u64,
+    desk_payment_treasury: &InterfaceAccount<'info, TokenAccount>,
+    bidder_usdc_ata: &InterfaceAccount<'info, TokenAccount>,
+    usdc_mint: &InterfaceAccount<'info, Mint>,
+    treasury_authority: &UncheckedAccount<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if amount > 0 {
+        let desk_key = desk.key();
+        let bump = [desk.treasury_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+        let cpi_accounts = TransferChecked {
+            from: desk_payment_treasury.to_account_info(),
+            to: bidder_usdc_ata.to_account_info(),
+            authority: treasury_authority.to_account_info(),
+            mint: usdc_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_ctx, amount, payment_registry.decimals)?;
+        payment_registry.bid_escrow = payment_registry.bid_escrow.checked_sub(amount).ok_or(OtcError::Overflow)?;
+    }
+    auction.released_count = auction.released_count.checked_add(1).ok_or(OtcError::Overflow)?;
+    Ok(())
+}
+
+/// A consignment with max_time_to_execute_secs == 0 never expires
+fn consignment_expired(consignment: &Consignment, now: i64) -> Result<bool> {
+    if consignment.max_time_to_execute_secs == 0 {
+        return Ok(false);
+    }
+    let expires_at = consignment.created_at.checked_add(consignment.max_time_to_execute_secs).ok_or(OtcError::Overflow)?;
+    Ok(now >= expires_at)
+}
+
+/// Move newly paid consignment offer tokens into the reserved bucket
+/// Direct offers already reserved their desk-owned inventory in create_offer
+fn reserve_for_paid_offer(registry: &mut TokenRegistry, offer: &Offer, token_amount: u64) -> Result<()> {
+    if offer.consignment_id > 0 {
+        registry.consigned_amount = registry.consigned_amount.checked_sub(token_amount).ok_or(OtcError::InsuffInv)?;
+        registry.reserved_amount = registry.reserved_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    }
+    Ok(())
+}
+
+/// Transfer a freshly paid offer's fully vested tokens to the beneficiary
+#[allow(clippy::too_many_arguments)]
+fn deliver_offer_tokens<'info>(
+    desk: &Account<'info, Desk>,
+    offer: &mut Account<'info, Offer>,
+    registry: &mut Account<'info, TokenRegistry>,
+    desk_token_treasury: &InterfaceAccount<'info, TokenAccount>,
+    beneficiary_token_ata: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    treasury_authority: &UncheckedAccount<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let amount = offer.token_amount.checked_sub(offer.claimed_amount).ok_or(OtcError::Overflow)?;
+    let desk_key = desk.key();
+    let bump = [desk.treasury_bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+    let cpi_accounts = TransferChecked {
+        from: desk_token_treasury.to_account_info(),
+        to: beneficiary_token_ata.to_account_info(),
+        authority: treasury_authority.to_account_info(),
+        mint: token_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    transfer_checked(cpi_ctx, amount, offer.token_decimals)?;
+    
+    registry.reserved_amount = registry.reserved_amount.checked_sub(amount).ok_or(OtcError::Overflow)?;
+    offer.claimed_amount = offer.token_amount;
+    offer.fulfilled = true;
+    emit!(TokensClaimed { offer: offer.key(), beneficiary: offer.beneficiary, amount });
+    Ok(())
+}
+
+/// Validate a fill tranche against the offer's unfilled remainder
+/// Tranches below the desk minimum are only accepted when they close out the offer
+fn check_fill(desk: &Desk, offer: &Offer, payer: &Pubkey, token_amount: u64, now: i64) -> Result<()> {
+    require!(offer.approved, OtcError::NotApproved);
+    // SECURITY: other payers may only take tranches of an offer its beneficiary opened to syndication
+    require!(offer.syndicated || *payer == offer.beneficiary, OtcError::FulfillRestricted);
+    require!(!offer.cancelled && !offer.paid && !offer.fulfilled, OtcError::BadState);
+    require!(offer.award_deposit == 0, OtcError::BadState); // Deposit-backed awards are paid in one fulfilment
+    require!(now <= offer.expires_at, OtcError::Expired);
+    let unfilled = offer.token_amount.checked_sub(offer.filled_amount).ok_or(OtcError::Overflow)?;
+    require!(token_amount > 0 && token_amount <= unfilled, OtcError::AmountRange);
+    if token_amount < unfilled {
+        let usd_8d = calc_discounted_usd(token_amount, offer.price_usd_per_token_8d, offer.token_decimals, offer.discount_bps)?;
+        require!(usd_8d >= desk.min_usd_amount_8d, OtcError::MinUsd);
+    }
+    Ok(())
+}
+
+/// Validate a single payment for the whole offer
+fn check_fulfill(offer: &Offer, now: i64) -> Result<()> {
+    require!(offer.approved, OtcError::NotApproved);
+    require!(!offer.cancelled && !offer.paid && !offer.fulfilled, OtcError::BadState);
+    require!(offer.filled_amount == 0, OtcError::BadState); // Already being filled in tranches
+    require!(now <= offer.expires_at, OtcError::Expired);
+    Ok(())
+}
+
+/// Checks shared by every offer payment: live token price and restricted fulfilment,
+/// then move the paid tokens into the reserved bucket
+fn begin_offer_payment(
+    desk: &Account<Desk>,
+    offer: &Offer,
+    registry: &mut TokenRegistry,
+    payer: Pubkey,
+    approver_account: &Option<Account<Approver>>,
+    token_amount: u64,
+    now: i64,
+) -> Result<()> {
+    check_live_token_price(registry, offer, desk.max_price_age_secs, now)?;
+    if desk.restrict_fulfill {
+        require!(payer == offer.beneficiary || payer == desk.owner || payer == desk.agent || is_approver(desk.key(), &payer, approver_account), OtcError::FulfillRestricted);
+    }
+    reserve_for_paid_offer(registry, offer, token_amount)
+}
+
+/// Price token_amount of an offer in payment mint units: (payment, agent commission taken out of it)
+/// SOL offers without a recorded payment price fall back to desk.sol_usd_price_8d
+fn offer_payment(desk: &Desk, offer: &Offer, token_amount: u64, payment_decimals: u8) -> Result<(u64, u64)> {
+    let payment_usd_price_8d = if offer.payment_usd_price_8d == 0 && offer.payment_mint == NATIVE_SOL { desk.sol_usd_price_8d } else { offer.payment_usd_price_8d };
+    require!(payment_usd_price_8d > 0, OtcError::NoPrice);
+    let usd_8d = calc_discounted_usd(token_amount, offer.price_usd_per_token_8d, offer.token_decimals, offer.discount_bps)?;
+    let payment = safe_u128_to_u64(mul_div_ceil_u128(usd_8d as u128, pow10(payment_decimals as u32), payment_usd_price_8d as u128)?)?;
+    let commission_usd_8d = usd_8d.checked_mul(offer.agent_commission_bps as u64).ok_or(OtcError::Overflow)?.checked_div(10_000).ok_or(OtcError::Overflow)?;
+    let commission = safe_u128_to_u64(mul_div_u128(commission_usd_8d as u128, pow10(payment_decimals as u32), payment_usd_price_8d as u128)?)?;
+    Ok((payment, commission))
+}
+
+/// Move lamports out of a program-owned account, which must stay rent-exempt afterwards
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> Result<()> {
+    let min_rent = Rent::get()?.minimum_balance(from.data_len());
+    let remaining = from.lamports().checked_sub(lamports).ok_or(OtcError::Overflow)?;
+    require!(remaining >= min_rent, OtcError::BadState);
+    let received = to.lamports().checked_add(lamports).ok_or(OtcError::Overflow)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = received;
+    Ok(())
+}
+
+/// Pull an SPL payment into the desk treasury and forward the agent's commission out of it
+/// SECURITY: the commission only goes to an account owned by desk.agent
+#[allow(clippy::too_many_arguments)]
+fn collect_spl_payment<'info>(
+    desk: &Account<'info, Desk>,
+    offer: &Account<'info, Offer>,
+    amount: u64,
+    commission: u64,
+    decimals: u8,
+    payer: &Signer<'info>,
+    payer_payment_ata: &InterfaceAccount<'info, TokenAccount>,
+    desk_payment_treasury: &InterfaceAccount<'info, TokenAccount>,
+    agent_payment_ata: &Option<InterfaceAccount<'info, TokenAccount>>,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    treasury_authority: &UncheckedAccount<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: payer_payment_ata.to_account_info(),
+        to: desk_payment_treasury.to_account_info(),
+        authority: payer.to_account_info(),
+        mint: payment_mint.to_account_info(),
+    };
+    transfer_checked(CpiContext::new(token_program.to_account_info(), cpi_accounts), amount, decimals)?;
+
+    // Commission is paid only when the agent's account is supplied
+    if commission > 0 {
+        if let Some(agent_payment_ata) = agent_payment_ata {
+            require!(agent_payment_ata.owner == desk.agent, OtcError::BadState);
+            let desk_key = desk.key();
+            let bump = [desk.treasury_bump];
+            let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, desk_key.as_ref(), &bump]];
+            let cpi_accounts = TransferChecked {
+                from: desk_payment_treasury.to_account_info(),
+                to: agent_payment_ata.to_account_info(),
+                authority: treasury_authority.to_account_info(),
+                mint: payment_mint.to_account_info(),
+            };
+            transfer_checked(CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds), commission, decimals)?;
+            emit!(AgentCommissionPaid { offer: offer.key(), agent: desk.agent, amount: commission, payment_mint: offer.payment_mint });
+        }
+    }
+    Ok(())
+}
+
+/// Pull a SOL payment into the desk and forward the agent's commission out of it
+/// SECURITY: the commission only goes to desk.agent
+fn collect_sol_payment<'info>(
+    desk: &Account<'info, Desk>,
+    offer: &Account<'info, Offer>,
+    lamports: u64,
+    commission: u64,
+    payer: &Signer<'info>,
+    agent: &Option<AccountInfo<'info>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let desk_ai = desk.to_account_info();
+    let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.key(), &desk_ai.key(), lamports);
+    anchor_lang::solana_program::program::invoke(&ix, &[payer.to_account_info(), desk_ai.clone(), system_program.to_account_info()])?;
+
+    // Commission is paid only when the agent's account is supplied; the desk is program-owned, so no signer is needed
+    if commission > 0 {
+        if let Some(agent_account) = agent {
+            require!(agent_account.key() == desk.agent, OtcError::BadState);
+            move_lamports(&desk_ai, agent_account, commission)?;
+            emit!(AgentCommissionPaid { offer: offer.key(), agent: desk.agent, amount: commission, payment_mint: NATIVE_SOL });
+        }
+    }
+    Ok(())
+}
+
+/// Credit a consignment sale's seller proceeds (payment less commission) to the consigner's ledger
+/// and to owed_total, the payment registry's or the desk's SOL total of unwithdrawn proceeds
+/// Returns the amount credited; desk-owned sales credit nothing
+fn credit_consigner_proceeds<'info>(
+    offer: &Account<'info, Offer>,
+    desk_key: Pubkey,
+    payment_amount: u64,
+    commission: u64,
+    consignment: &Option<Account<'info, Consignment>>,
+    consigner_proceeds: &mut Option<Account<'info, ConsignerProceeds>>,
+    owed_total: &mut u64,
+) -> Result<u64> {
+    if offer.consignment_id == 0 {
+        return Ok(0);
+    }
+    let proceeds = payment_amount.checked_sub(commission).ok_or(OtcError::Overflow)?;
+    let consigner_proceeds = consigner_proceeds_for(offer, desk_key, consignment, consigner_proceeds)?;
+    consigner_proceeds.owed = consigner_proceeds.owed.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+    *owed_total = owed_total.checked_add(proceeds).ok_or(OtcError::Overflow)?;
+    emit!(ConsignerProceedsCredited { offer: offer.key(), consigner: consigner_proceeds.consigner, amount: proceeds, payment_mint: offer.payment_mint });
+    Ok(proceeds)
+}
+
+/// Check an emergency refund is enabled, past its deadline and requested by someone entitled to it
+fn check_emergency_refund(desk: &Account<Desk>, offer: &Offer, payer: Pubkey, caller: Pubkey, approver_account: &Option<Account<Approver>>) -> Result<()> {
+    require!(desk.emergency_refund_enabled, OtcError::BadState);
+    let now = Clock::get()?.unix_timestamp;
+    let deadline = offer.created_at.checked_add(desk.emergency_refund_deadline_secs).ok_or(OtcError::Overflow)?;
+    let unlock_deadline = offer.unlock_time.checked_add(30 * 86400).ok_or(OtcError::Overflow)?; // 30 days after unlock
+    require!(now >= deadline || now >= unlock_deadline, OtcError::TooEarlyForRefund);
+    require!(
+        caller == payer ||
+        caller == offer.beneficiary ||
+        caller == desk.owner ||
+        caller == desk.agent ||
+        is_approver(desk.key(), &caller, approver_account),
+        OtcError::NotOwner
+    );
+    Ok(())
+}
+
+/// Put a refunded payment's tokens back where they came from and reverse the consigner proceeds
+/// credited for it; owed_total as in credit_consigner_proceeds
+fn unwind_offer_payment<'info>(
+    offer: &Offer,
+    desk_key: Pubkey,
+    token_amount: u64,
+    credited: u64,
+    registry: &mut TokenRegistry,
+    consignment: &mut Option<Account<'info, Consignment>>,
+    consigner_proceeds: &mut Option<Account<'info, ConsignerProceeds>>,
+    owed_total: &mut u64,
+) -> Result<()> {
+    release_paid_offer(registry, offer, token_amount)?;
+    restore_consignment_inventory(offer, token_amount, desk_key, consignment.as_deref_mut())?;
+    if credited == 0 {
+        return Ok(());
+    }
+    let consigner_proceeds = consigner_proceeds_for(offer, desk_key, consignment, consigner_proceeds)?;
+    claw_back_consigner_proceeds(credited, consigner_proceeds)?;
+    *owed_total = owed_total.checked_sub(credited).ok_or(OtcError::Overflow)?;
+    Ok(())
+}
+
+/// Add a paid tranche to the payer's fill record and the offer's running totals
+fn record_fill(
+    offer: &mut Offer,
+    fill: &mut OfferFill,
+    offer_key: Pubkey,
+    payer_key: Pubkey,
+    token_amount: u64,
+    amount_paid: u64,
+    consigner_proceeds: u64,
+) -> Result<()> {
+    if fill.offer == Pubkey::default() {
+        fill.offer = offer_key;
+        fill.payer = payer_key;
+        offer.fill_count = offer.fill_count.checked_add(1).ok_or(OtcError::Overflow)?;
+    }
+    fill.token_amount = fill.token_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    fill.amount_paid = fill.amount_paid.checked_add(amount_paid).ok_or(OtcError::Overflow)?;
+    fill.consigner_proceeds = fill.consigner_proceeds.checked_add(consigner_proceeds).ok_or(OtcError::Overflow)?;
+    offer.filled_amount = offer.filled_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    offer.amount_paid = offer.amount_paid.checked_add(amount_paid).ok_or(OtcError::Overflow)?;
+    offer.consigner_proceeds = offer.consigner_proceeds.checked_add(consigner_proceeds).ok_or(OtcError::Overflow)?;
+    offer.paid = offer.filled_amount == offer.token_amount;
+    Ok(())
+}
+
+/// Close an unpaid offer and return its unfilled token amount
+/// A partially filled offer is trimmed to its filled size and stays claimable by its fillers;
+/// offer.payer stays unset since each tranche's payer is on its OfferFill
+fn close_unfilled_offer(offer: &mut Offer) -> Result<u64> {
+    let unfilled = offer.token_amount.checked_sub(offer.filled_amount).ok_or(OtcError::Overflow)?;
+    if offer.filled_amount > 0 {
+        offer.token_amount = offer.filled_amount;
+        offer.paid = true;
+    } else {
+        offer.cancelled = true;
+    }
+    Ok(unfilled)
+}
+
+/// Take a refunded tranche out of its syndicated offer; an offer left with no tranches is cancelled
+fn remove_refunded_fill(offer: &mut Offer, fill: &mut OfferFill) -> Result<()> {
+    offer.token_amount = offer.token_amount.checked_sub(fill.token_amount).ok_or(OtcError::Overflow)?;
+    offer.filled_amount = offer.filled_amount.checked_sub(fill.token_amount).ok_or(OtcError::Overflow)?;
+    offer.amount_paid = offer.amount_paid.checked_sub(fill.amount_paid).ok_or(OtcError::Overflow)?;
+    offer.consigner_proceeds = offer.consigner_proceeds.checked_sub(fill.consigner_proceeds).ok_or(OtcError::Overflow)?;
+    if offer.token_amount == 0 {
+        offer.cancelled = true;
+    } else {
+        offer.fulfilled = offer.claimed_amount == offer.token_amount;
+    }
+    fill.token_amount = 0;
+    fill.amount_paid = 0;
+    fill.consigner_proceeds = 0;
+    Ok(())
+}
+
+/// Return a refunded offer's (or tranche's) reserved tokens to the inventory bucket they came from
+fn release_paid_offer(registry: &mut TokenRegistry, offer: &Offer, token_amount: u64) -> Result<()> {
+    registry.reserved_amount = registry.reserved_amount.checked_sub(token_amount).ok_or(OtcError::Overflow)?;
+    if offer.consignment_id > 0 {
+        registry.consigned_amount = registry.consigned_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    } else {
+        registry.desk_owned_amount = registry.desk_owned_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    }
+    Ok(())
+}
+
+/// Return a refunded or cancelled consignment offer's tokens to its source consignment
+/// An inactive consignment stays inactive here; the consigner can pick the tokens up with withdraw_consignment
+fn restore_consignment_inventory(offer: &Offer, token_amount: u64, desk_key: Pubkey, consignment: Option<&mut Consignment>) -> Result<()> {
+    if offer.consignment_id == 0 {
+        return Ok(());
+    }
+    let consignment = consignment.ok_or(OtcError::BadState)?;
+    require!(consignment.desk == desk_key && consignment.id == offer.consignment_id, OtcError::BadState);
+    consignment.remaining_amount = consignment.remaining_amount.checked_add(token_amount).ok_or(OtcError::Overflow)?;
+    Ok(())
+}
+
+/// Resolve the proceeds ledger of the consigner behind a consignment offer
+fn consigner_proceeds_for<'a, 'info>(
+    offer: &Offer,
+    desk_key: Pubkey,
+    consignment: &Option<Account<'info, Consignment>>,
+    consigner_proceeds: &'a mut Option<Account<'info, ConsignerProceeds>>,
+) -> Result<&'a mut Account<'info, ConsignerProceeds>> {
+    let consignment = consignment.as_ref().ok_or(OtcError::BadState)?;
+    require!(consignment.desk == desk_key && consignment.id == offer.consignment_id, OtcError::BadState);
+    let proceeds = consigner_proceeds.as_mut().ok_or(OtcError::BadState)?;
+    require!(proceeds.desk == desk_key && proceeds.consigner == consignment.consigner && proceeds.payment_mint == offer.payment_mint, OtcError::BadState);
+    Ok(proceeds)
+}
+/// Take a refunded payment's proceeds back off the consigner's ledger
+/// Once the consigner has withdrawn them the sale stands: refunding the buyer would hand the
+/// tokens back to the consignment while the consigner keeps the payment
+fn claw_back_consigner_proceeds(credited: u64, proceeds: &mut ConsignerProceeds) -> Result<()> {
+    require!(credited <= proceeds.owed, OtcError::BadState);
+    proceeds.owed = proceeds.owed.checked_sub(credited).ok_or(OtcError::Overflow)?;
+    Ok(())
+}
+/// USD price (8d) of an offer's payment mint at creation time
+fn payment_price_for_offer(desk: &Desk, desk_key: Pubkey, payment_mint: Pubkey, payment_registry: &Option<Account<PaymentRegistry>>, now: i64) -> Result<u64> {
+    if payment_mint == NATIVE_SOL {
+        require!(desk.sol_usd_price_8d > 0, OtcError::NoPrice);
+        require!(now - desk.prices_updated_at <= desk.max_price_age_secs, OtcError::StalePrice);
+        return Ok(desk.sol_usd_price_8d);
+    }
+    let registry = payment_registry.as_ref().ok_or(OtcError::UnsupportedCurrency)?;
+    require!(registry.desk == desk_key && registry.mint == payment_mint && registry.is_active, OtcError::UnsupportedCurrency);
+    if registry.is_pegged {
+        return Ok(USD_PEG_8D);
+    }
+    require!(registry.usd_price_8d > 0, OtcError::NoPrice);
+    require!(now - registry.prices_updated_at <= desk.max_price_age_secs, OtcError::StalePrice);
+    Ok(registry.usd_price_8d)
+}
 fn mul_div_u128(a: u128, b: u128, d: u128) -> Result<u128> { a.checked_mul(b).and_then(|x| x.checked_div(d)).ok_or(OtcError::Overflow.into()) }
 fn mul_div_ceil_u128(a: u128, b: u128, d: u128) -> Result<u128> { let prod = a.checked_mul(b).ok_or(OtcError::Overflow)?; let q = prod / d; let r = prod % d; Ok(if r == 0 { q } else { q + 1 }) }
 fn safe_u128_to_u64(value: u128) -> Result<u64> { u64::try_from(value).map_err(|_| OtcError::Overflow.into()) }
@@ -1688,12 +5002,185 @@ fn check_price_deviation(old_price: u64, new_price: u64, max_deviation_bps: u16)
     if old_price == 0 || max_deviation_bps == 0 {
         return Ok(());
     }
-    let diff = if new_price > old_price { new_price - old_price } else { old_price - new_price };
+    let diff = new_price.abs_diff(old_price);
     let max_deviation = (old_price as u128 * max_deviation_bps as u128) / 10000u128;
     require!(diff as u128 <= max_deviation, OtcError::PriceDeviationTooLarge);
     Ok(())
 }
 
+/// Shared validation and setup for offers carved out of a consignment
+fn open_consignment_offer<'info>(
+    desk: &mut Account<'info, Desk>,
+    consignment: &mut Account<'info, Consignment>,
+    registry: &Account<'info, TokenRegistry>,
+    payment_registry: &Option<Account<'info, PaymentRegistry>>,
+    approver_account: &Option<Account<'info, Approver>>,
+    offer: &mut Account<'info, Offer>,
+    beneficiary: Pubkey,
+    terms: &OfferTerms,
+    agent_quoted: bool,
+) -> Result<()> {
+    let desk_key = desk.key();
+    require!(!desk.paused, OtcError::Paused);
+
+    require!(consignment.is_active && !consignment.is_sealed_bid, OtcError::BadState);
+    let now = Clock::get()?.unix_timestamp;
+    require!(!consignment_expired(consignment, now)?, OtcError::Expired);
+    
+    // Enforce is_private: only consigner, owner, agent, or approvers can create offers
+    // An agent-signed quote counts as the agent inviting the beneficiary
+    if consignment.is_private && !agent_quoted {
+        let caller = beneficiary;
+        require!(
+            caller == consignment.consigner || 
+            caller == desk.owner || 
+            caller == desk.agent || 
+            is_approver(desk_key, &caller, approver_account),
+            OtcError::FulfillRestricted
+        );
+    }
+    let OfferTerms {
+        token_amount,
+        discount_bps,
+        payment_mint,
+        lockup_secs,
+        agent_commission_bps,
+        vesting_cliff_secs,
+        vesting_duration_secs,
+        vesting_period_secs,
+        quote_lifetime_secs,
+    } = *terms;
+    
+    require!(token_amount >= consignment.min_deal_amount && token_amount <= consignment.max_deal_amount, OtcError::AmountRange);
+    require!(token_amount <= consignment.remaining_amount, OtcError::InsuffInv);
+    if !consignment.is_fractionalized {
+        require!(token_amount == consignment.remaining_amount, OtcError::AmountRange);
+    }
+    validate_vesting(vesting_cliff_secs, vesting_duration_secs, vesting_period_secs)?;
+
+    // Dutch auctions price the discount from the clock; the caller-supplied value is ignored
+    let discount_bps = if consignment.is_dutch_auction { dutch_auction_discount_bps(consignment, now)? } else { discount_bps };
+
+    // Determine effective commission for the offer
+    let effective_commission_bps: u16 = if consignment.is_negotiable {
+        require!(discount_bps >= consignment.min_discount_bps && discount_bps <= consignment.max_discount_bps, OtcError::Discount);
+        let lockup_days = lockup_secs / 86400;
+        require!(lockup_days >= consignment.min_lockup_days as i64 && lockup_days <= consignment.max_lockup_days as i64, OtcError::LockupTooLong);
+        // Negotiated vesting may be shorter than the consignment's limits, never longer
+        require!(vesting_cliff_secs <= consignment.vesting_cliff_days as i64 * 86400, OtcError::InvalidVesting);
+        require!(vesting_duration_secs <= consignment.vesting_duration_days as i64 * 86400, OtcError::InvalidVesting);
+        require!(vesting_period_secs <= consignment.vesting_period_days as i64 * 86400, OtcError::InvalidVesting);
+        // Negotiated deals: commission must be 25-150 bps (0.25% - 1.5%)
+        require!(agent_commission_bps >= 25 && agent_commission_bps <= 150, OtcError::CommissionRange);
+        agent_commission_bps
+    } else {
+        if !consignment.is_dutch_auction {
+            require!(discount_bps == consignment.fixed_discount_bps, OtcError::Discount);
+        }
+        let lockup_days = lockup_secs / 86400;
+        require!(lockup_days == consignment.fixed_lockup_days as i64, OtcError::LockupTooLong);
+        require!(
+            vesting_cliff_secs == consignment.vesting_cliff_days as i64 * 86400 &&
+            vesting_duration_secs == consignment.vesting_duration_days as i64 * 86400 &&
+            vesting_period_secs == consignment.vesting_period_days as i64 * 86400,
+            OtcError::InvalidVesting
+        );
+        // P2P deals: use the configured p2p_commission_bps (default 0.25%)
+        // agent_commission_bps parameter is ignored for P2P - uses desk-wide setting
+        desk.p2p_commission_bps
+    };
+
+    // Use registry price for multi-token support
+    require!(registry.token_mint == consignment.token_mint, OtcError::BadState); // Ensure registry matches consignment
+    
+    let price_8d = registry.token_usd_price_8d;
+    require!(price_8d > 0, OtcError::NoPrice);
+    
+    // Check registry price age
+    if registry.prices_updated_at > 0 {
+        require!(now - registry.prices_updated_at <= desk.max_price_age_secs, OtcError::StalePrice);
+    }
+    let payment_usd_price_8d = payment_price_for_offer(desk, desk_key, payment_mint, payment_registry, now)?;
+
+    // Check implied USD value meets minimum
+    let total_usd_disc = calc_discounted_usd(token_amount, price_8d, registry.decimals, discount_bps)?;
+    require!(total_usd_disc >= desk.min_usd_amount_8d, OtcError::MinUsd);
+
+    consignment.remaining_amount = consignment.remaining_amount.checked_sub(token_amount).ok_or(OtcError::Overflow)?;
+    if consignment.remaining_amount == 0 {
+        consignment.is_active = false;
+    }
+
+    let offer_id = desk.next_offer_id;
+    desk.next_offer_id = offer_id.checked_add(1).ok_or(OtcError::Overflow)?;
+
+    let offer_key = offer.key();
+    let beneficiary_key = beneficiary;
+    
+    // Non-negotiable offers are auto-approved for P2P (permissionless)
+    // Negotiable offers require agent/approver approval; an agent quote is the agent's approval
+    let approval_count: u8 = if agent_quoted { 1 } else { 0 };
+    let auto_approved = !consignment.is_negotiable || approval_count >= required_approvals_for(desk, total_usd_disc);
+    
+    offer.desk = desk_key;
+    offer.consignment_id = consignment.id;
+    offer.token_mint = consignment.token_mint;
+    offer.token_decimals = registry.decimals;
+    offer.id = offer_id;
+    offer.beneficiary = beneficiary_key;
+    offer.token_amount = token_amount;
+    offer.discount_bps = discount_bps;
+    offer.created_at = now;
+    offer.unlock_time = now.checked_add(lockup_secs).ok_or(OtcError::Overflow)?;
+    offer.price_usd_per_token_8d = price_8d;
+    offer.max_price_deviation_bps = consignment.max_price_volatility_bps;
+    offer.payment_usd_price_8d = payment_usd_price_8d;
+    offer.payment_mint = payment_mint;
+    offer.approved = auto_approved;
+    offer.paid = false;
+    offer.fulfilled = false;
+    offer.cancelled = false;
+    offer.payer = Pubkey::default();
+    offer.amount_paid = 0;
+    offer.agent_commission_bps = effective_commission_bps;
+    offer.consigner_proceeds = 0;
+    offer.vesting_start = offer.unlock_time;
+    offer.vesting_cliff_secs = vesting_cliff_secs;
+    offer.vesting_duration_secs = vesting_duration_secs;
+    offer.vesting_period_secs = vesting_period_secs;
+    offer.claimed_amount = 0;
+    offer.approval_count = approval_count;
+    offer.expires_at = offer_expires_at(desk, consignment.max_quote_lifetime_secs, quote_lifetime_secs, now)?;
+    offer.filled_amount = 0;
+    offer.fill_count = 0;
+    offer.syndicated = false;
+    offer.award_deposit = 0;
+
+    emit!(OfferCreated {
+        desk: offer.desk,
+        offer: offer_key,
+        beneficiary: beneficiary_key,
+        token_amount,
+        discount_bps,
+        payment_mint
+    });
+    
+    // Emit approval event for non-negotiable (P2P) and agent-quoted offers
+    if auto_approved {
+        let approver = if agent_quoted { desk.agent } else { beneficiary_key };
+        emit!(OfferApproved { offer: offer_key, approver });
+    }
+    
+    Ok(())
+}
+
+/// Reject fulfilment if the token price is stale or has moved past the offer's volatility limit
+fn check_live_token_price(registry: &TokenRegistry, offer: &Offer, max_price_age_secs: i64, now: i64) -> Result<()> {
+    require!(registry.token_usd_price_8d > 0, OtcError::NoPrice);
+    require!(now.saturating_sub(registry.prices_updated_at) <= max_price_age_secs, OtcError::StalePrice);
+    check_price_deviation(offer.price_usd_per_token_8d, registry.token_usd_price_8d, offer.max_price_deviation_bps)
+}
+
 fn calc_discounted_usd(token_amount: u64, price_8d: u64, decimals: u8, discount_bps: u16) -> Result<u64> {
     let token_dec = decimals as u32;
     let usd_8d = safe_u128_to_u64(mul_div_u128(token_amount as u128, price_8d as u128, pow10(token_dec) as u128)?)?;
@@ -1734,7 +5221,7 @@ fn convert_pyth_price(price: i64, exponent: i32) -> Result<u64> {
 
 #[error_code]
 pub enum OtcError {
-    #[msg("USDC must have 6 decimals")] UsdcDecimals,
+    #[msg("USDC must have 6 decimals")] UsdcDecimals, // no longer raised; kept so later error codes don't shift
     #[msg("Amount out of range")] AmountRange,
     #[msg("Discount too high")] Discount,
     #[msg("Price data is stale")] StalePrice,
@@ -1765,6 +5252,20 @@ pub enum OtcError {
     #[msg("Price update too frequent")] UpdateTooFrequent,
     #[msg("Commission must be 0 for P2P or 25-150 bps for negotiated")] CommissionRange,
     #[msg("Non-negotiable offers are P2P (auto-approved)")] NonNegotiableP2P,
+    #[msg("Invalid vesting schedule")] InvalidVesting,
+    #[msg("Too many owners")] TooManyOwners,
+    #[msg("Threshold exceeds owner count")] InvalidThreshold,
+    #[msg("Multisig proposal required")] ProposalRequired,
+    #[msg("Proposal does not match this action")] ProposalMismatch,
+    #[msg("Change must be queued through the timelock")] TimelockRequired,
+    #[msg("Timelock has not elapsed")] TimelockNotElapsed,
+    #[msg("Signer lacks the required role")] MissingRole,
+    #[msg("Approver USD limit exceeded")] ApprovalLimitExceeded,
+    #[msg("Senior approver required")] SeniorApprovalRequired,
+    #[msg("Missing or invalid agent quote signature")] InvalidQuoteSignature,
+    #[msg("Payment exceeds max_payment_amount")] SlippageExceeded,
+    #[msg("Auction bid limit reached")] TooManyBids,
+    #[msg("Revealed bid does not match commitment")] InvalidBidReveal,
 }
 
 
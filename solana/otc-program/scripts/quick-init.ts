@@ -175,8 +175,7 @@ async function main() {
     .setPrices(
       new BN(1_000_000_000),      // token price (deprecated, kept for compatibility)
       new BN(100_000_000_00),     // SOL price: $100 (8 decimals)
-      new BN(0),                   // updated_at (ignored, uses clock)
-      new BN(3600)                 // max age
+      new BN(0)                    // updated_at (ignored, uses clock)
     )
     .accountsPartial({ desk: desk.publicKey, owner: owner.publicKey })
     .signers([owner])
@@ -135,8 +135,7 @@ async function main() {
         .setPrices(
           new anchor.BN(0), // token price (deprecated)
           solUsd8d,
-          new anchor.BN(0), // updated_at (uses clock)
-          new anchor.BN(3600) // max age 1 hour
+          new anchor.BN(0) // updated_at (uses clock)
         )
         .accountsPartial({
           desk: DESK,
@@ -215,8 +214,7 @@ async function main() {
     .setPrices(
       new anchor.BN(0), // token price (deprecated)
       solUsd8d,
-      new anchor.BN(0), // updated_at (uses clock)
-      new anchor.BN(3600) // max age 1 hour
+      new anchor.BN(0) // updated_at (uses clock)
     )
     .accountsPartial({
       desk: DESK,
@@ -1,7 +1,6 @@
 import type { Program } from "@coral-xyz/anchor";
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 import * as fs from "fs";
 import type { Otc } from "../target/types/otc";
 
@@ -17,15 +16,6 @@ async function test() {
   const owner = Keypair.fromSecretKey(Uint8Array.from(ownerData));
 
   const desk = new PublicKey("7EN1rubej95WmoyupRXQ78PKU2hTCspKn2mVKN1vxuPp");
-  const tokenMint = new PublicKey(
-    "5QLcgYPdVSgZrS84TecffohCJHWC1eEkftnK6DKtpxg9"
-  );
-  const deskTokenTreasury = getAssociatedTokenAddressSync(
-    tokenMint,
-    desk,
-    true
-  );
-
   const offer = Keypair.generate();
 
   console.log("Testing createOffer with 1000 tokens...");
@@ -39,7 +29,6 @@ async function test() {
     )
     .accountsPartial({
       desk,
-      deskTokenTreasury,
       beneficiary: owner.publicKey,
       offer: offer.publicKey,
     })
@@ -37,11 +37,54 @@ describe("otc adversarial", () => {
     )[0];
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getApproverPda = (desk: PublicKey, approver: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("approver"), desk.toBuffer(), approver.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getParamChangePda = (desk: PublicKey, changeId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("param_change"), desk.toBuffer(), changeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Economic and oracle parameters go through the desk timelock (zero delay on a fresh desk)
+  const applyParamChange = async (
+    deskKey: PublicKey,
+    signer: Keypair,
+    change: anchor.IdlTypes<Otc>["paramChange"],
+    registry: PublicKey | null = null
+  ) => {
+    const { nextParamChangeId } = await program.account.desk.fetch(deskKey);
+    const pendingChange = getParamChangePda(deskKey, nextParamChangeId);
+    await program.methods
+      .queueParamChange(change)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange })
+      .signers([signer])
+      .rpc();
+    await program.methods
+      .executeParamChange(nextParamChangeId)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange, tokenRegistry: registry })
+      .signers([signer])
+      .rpc();
+  };
+
   let owner: Keypair;
   let attacker: Keypair;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let attackerDesk: Keypair;
   let tokenRegistry: PublicKey;
   let attackerRegistry: PublicKey;
@@ -50,6 +93,7 @@ describe("otc adversarial", () => {
     owner = Keypair.generate();
     attacker = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
     attackerDesk = Keypair.generate();
 
     await Promise.all([
@@ -63,12 +107,12 @@ describe("otc adversarial", () => {
 
     tokenRegistry = getTokenRegistryPda(desk.publicKey, tokenMint);
 
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, desk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, desk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, treasuryAuthority, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, treasuryAuthority, true);
 
     await program.methods
       .initDesk(new BN(500000000), new BN(1800))
-      .accounts({ owner: owner.publicKey, agent: owner.publicKey, usdcMint, desk: desk.publicKey, payer: owner.publicKey })
+      .accounts({ owner: owner.publicKey, agent: owner.publicKey, usdcMint, desk: desk.publicKey, treasuryAuthority, payer: owner.publicKey })
       .signers([owner, desk])
       .rpc();
 
@@ -80,21 +124,21 @@ describe("otc adversarial", () => {
 
     await program.methods
       .setManualTokenPrice(new BN(1_000_000_000))
-      .accounts({ tokenRegistry, desk: desk.publicKey, owner: owner.publicKey })
+      .accounts({ tokenRegistry, desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
-    await program.methods.setPrices(new BN(1_000_000_000), new BN(100_000_000_00), new BN(0), new BN(3600))
-      .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+    await program.methods.setPrices(new BN(1_000_000_000), new BN(100_000_000_00), new BN(0))
+      .accounts({ desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
-    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, tokenMint, attackerDesk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, usdcMint, attackerDesk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, tokenMint, getTreasuryAuthorityPda(attackerDesk.publicKey), true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, usdcMint, getTreasuryAuthorityPda(attackerDesk.publicKey), true);
 
     await program.methods
       .initDesk(new BN(100000000), new BN(1800))
-      .accounts({ owner: attacker.publicKey, agent: attacker.publicKey, usdcMint, desk: attackerDesk.publicKey, payer: attacker.publicKey })
+      .accounts({ owner: attacker.publicKey, agent: attacker.publicKey, usdcMint, desk: attackerDesk.publicKey, treasuryAuthority: getTreasuryAuthorityPda(attackerDesk.publicKey), payer: attacker.publicKey })
       .signers([attacker, attackerDesk])
       .rpc();
 
@@ -108,7 +152,7 @@ describe("otc adversarial", () => {
 
     await program.methods
       .setManualTokenPrice(new BN(10_000_000))
-      .accounts({ tokenRegistry: attackerRegistry, desk: attackerDesk.publicKey, owner: attacker.publicKey })
+      .accounts({ tokenRegistry: attackerRegistry, desk: attackerDesk.publicKey, authority: attacker.publicKey })
       .signers([attacker])
       .rpc();
   });
@@ -120,7 +164,7 @@ describe("otc adversarial", () => {
         .accounts({
           tokenRegistry: tokenRegistry,
           desk: attackerDesk.publicKey,
-          owner: attacker.publicKey,
+          authority: attacker.publicKey,
         })
         .signers([attacker])
         .rpc(),
@@ -129,43 +173,42 @@ describe("otc adversarial", () => {
   });
 
   it("enforces desk ownership for treasury operations", async () => {
-    // This should fail because attacker is not the owner of the desk
+    // This should fail because attacker holds no treasurer role on the desk
     try {
       await program.methods
         .withdrawSol(new BN(1000000))
         .accounts({
           desk: desk.publicKey,
-          deskSigner: attackerDesk.publicKey,
           owner: attacker.publicKey,
           to: attacker.publicKey,
         })
-        .signers([attacker, attackerDesk])
+        .signers([attacker])
         .rpc();
       assert.fail("Expected to fail");
     } catch (error) {
       const msg = String(error).toLowerCase();
       assert.isTrue(
-        msg.includes("constraint") || msg.includes("owner") || msg.includes("notowner"),
-        `Expected ownership error but got: ${msg}`
+        msg.includes("missingrole"),
+        `Expected role error but got: ${msg}`
       );
     }
   });
 
   it("prevents configuring oracle on wrong registry", async () => {
     await expectRejectedWith(
-      program.methods
-        .configurePoolOracle(
-          new BN(10_000 * 1e6),
-          500,
-          new BN(60)
-        )
-        .accounts({
-          tokenRegistry: tokenRegistry,
-          desk: attackerDesk.publicKey,
-          owner: attacker.publicKey,
-        })
-        .signers([attacker])
-        .rpc(),
+      applyParamChange(
+        attackerDesk.publicKey,
+        attacker,
+        {
+          configurePoolOracle: {
+            tokenRegistry,
+            minLiquidity: new BN(10_000 * 1e6),
+            maxTwapDeviationBps: 500,
+            minUpdateIntervalSecs: new BN(60),
+          },
+        },
+        tokenRegistry
+      ),
       "BadState"
     );
   });
@@ -174,12 +217,8 @@ describe("otc adversarial", () => {
     const fakeFeed = new Array(32).fill(1);
     
     await expectRejectedWith(
-      program.methods
-        .setPythFeeds(fakeFeed, fakeFeed)
-        .accounts({ desk: desk.publicKey, owner: attacker.publicKey })
-        .signers([attacker])
-        .rpc(),
-      "Constraint"
+      applyParamChange(desk.publicKey, attacker, { setPythFeeds: { tokenFeedId: fakeFeed, solFeedId: fakeFeed } }),
+      "NotOwner"
     );
   });
 
@@ -190,7 +229,7 @@ describe("otc adversarial", () => {
         .accounts({ desk: desk.publicKey, owner: attacker.publicKey })
         .signers([attacker])
         .rpc(),
-      "Constraint"
+      "NotOwner"
     );
   });
 
@@ -201,28 +240,51 @@ describe("otc adversarial", () => {
         .accounts({ desk: desk.publicKey, owner: attacker.publicKey })
         .signers([attacker])
         .rpc(),
-      "Constraint"
+      "MissingRole"
     );
   });
 
-  it("validates approver count limit", async () => {
-    for (let i = 0; i < 32; i++) {
-      const approver = Keypair.generate();
+  it("registers approvers beyond the old 32-entry list", async () => {
+    // Approvers live in their own PDAs, so the desk no longer caps how many there are
+    const approvers = Array.from({ length: 33 }, () => Keypair.generate());
+    for (const approver of approvers) {
       await program.methods
-        .setApprover(approver.publicKey, true)
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .setApprover(approver.publicKey, new BN(1_000_000 * 1e8), new BN(10_000_000 * 1e8), false)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, approverAccount: getApproverPda(desk.publicKey, approver.publicKey) })
         .signers([owner])
         .rpc();
     }
 
-    const extraApprover = Keypair.generate();
+    const last = approvers[approvers.length - 1];
+    const record = await program.account.approver.fetch(getApproverPda(desk.publicKey, last.publicKey));
+    assert.equal(record.approver.toBase58(), last.publicKey.toBase58());
+  });
+
+  it("rejects an approver whose per-offer limit exceeds its daily limit", async () => {
+    const approver = Keypair.generate();
     await expectRejectedWith(
       program.methods
-        .setApprover(extraApprover.publicKey, true)
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .setApprover(approver.publicKey, new BN(2_000 * 1e8), new BN(1_000 * 1e8), false)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, approverAccount: getApproverPda(desk.publicKey, approver.publicKey) })
         .signers([owner])
         .rpc(),
-      "TooManyApprovers"
+      "AmountRange"
     );
   });
+
+  it("initializes a desk whose stablecoin is not 6-decimal", async () => {
+    const stableMint = await createMint(provider.connection, owner, owner.publicKey, null, 9);
+    const stableDesk = Keypair.generate();
+    const stableTreasuryAuthority = getTreasuryAuthorityPda(stableDesk.publicKey);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, stableMint, stableTreasuryAuthority, true);
+
+    await program.methods
+      .initDesk(new BN(100000000), new BN(1800))
+      .accounts({ owner: owner.publicKey, agent: owner.publicKey, usdcMint: stableMint, desk: stableDesk.publicKey, treasuryAuthority: stableTreasuryAuthority, payer: owner.publicKey })
+      .signers([owner, stableDesk])
+      .rpc();
+
+    const deskAccount = await program.account.desk.fetch(stableDesk.publicKey);
+    assert.equal(deskAccount.usdcDecimals, 9);
+  });
 });
@@ -7,6 +7,8 @@ import {
   PublicKey,
   SystemProgram,
   LAMPORTS_PER_SOL,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
 import {
   createMint,
@@ -17,6 +19,7 @@ import {
   getAccount,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { createHash } from "crypto";
 
 // Helper to assert promise rejects with specific error message
 async function expectRejectedWith(promise: Promise<unknown>, expectedError: string): Promise<void> {
@@ -46,23 +49,155 @@ describe("OTC Comprehensive Flow Tests", () => {
     )[0];
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getApproverPda = (desk: PublicKey, approver: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("approver"), desk.toBuffer(), approver.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getOfferApprovalPda = (offer: PublicKey, approver: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("approval"), offer.toBuffer(), approver.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getParamChangePda = (desk: PublicKey, changeId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("param_change"), desk.toBuffer(), changeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const getProposalPda = (desk: PublicKey, proposalId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), desk.toBuffer(), proposalId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Economic and oracle parameters go through the desk timelock (zero delay on a fresh desk)
+  const applyParamChange = async (
+    deskKey: PublicKey,
+    signer: Keypair,
+    change: anchor.IdlTypes<Otc>["paramChange"],
+    registry: PublicKey | null = null
+  ) => {
+    const { nextParamChangeId } = await program.account.desk.fetch(deskKey);
+    const pendingChange = getParamChangePda(deskKey, nextParamChangeId);
+    await program.methods
+      .queueParamChange(change)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange })
+      .signers([signer])
+      .rpc();
+    await program.methods
+      .executeParamChange(nextParamChangeId)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange, tokenRegistry: registry })
+      .signers([signer])
+      .rpc();
+  };
+
+  const setLimits = (
+    minUsdAmount8d: anchor.BN,
+    maxTokenPerOrder: anchor.BN,
+    quoteExpirySecs: anchor.BN,
+    defaultUnlockDelaySecs: anchor.BN,
+    maxLockupSecs: anchor.BN,
+    { signer = owner } = {}
+  ) =>
+    applyParamChange(desk.publicKey, signer, {
+      setLimits: { minUsdAmount8d, maxTokenPerOrder, quoteExpirySecs, defaultUnlockDelaySecs, maxLockupSecs },
+    });
+
+  const getPaymentRegistryPda = (desk: PublicKey, paymentMint: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("payment"), desk.toBuffer(), paymentMint.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getConsignerProceedsPda = (desk: PublicKey, consigner: PublicKey, paymentMint: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proceeds"), desk.toBuffer(), consigner.toBuffer(), paymentMint.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getConsignmentPda = (desk: PublicKey, consignmentId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("consignment"), desk.toBuffer(), consignmentId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const getOfferPda = (desk: PublicKey, offerId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("offer"), desk.toBuffer(), offerId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const getQuoteNoncePda = (desk: PublicKey, nonce: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_nonce"), desk.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Consignments and offers are created at the desk's next id
+  const nextConsignmentPda = async () => {
+    const deskAccount = await program.account.desk.fetch(desk.publicKey);
+    return getConsignmentPda(desk.publicKey, deskAccount.nextConsignmentId);
+  };
+
+  const nextOfferPda = async () => {
+    const deskAccount = await program.account.desk.fetch(desk.publicKey);
+    return getOfferPda(desk.publicKey, deskAccount.nextOfferId);
+  };
+
+  // The localnet clock cannot be warped, so time-based tests wait for the validator to catch up
+  const waitForChainTime = async (unixTs: number) => {
+    for (;;) {
+      const blockTime = await provider.connection.getBlockTime(await provider.connection.getSlot());
+      if (blockTime !== null && blockTime >= unixTs) return;
+      await new Promise(r => setTimeout(r, 500));
+    }
+  };
+
+  // Slippage caps for fulfilment, sized to what test buyers are funded with
+  const MAX_USDC_PAYMENT = new anchor.BN(1_000_000 * 1e6);
+  const MAX_SOL_PAYMENT = new anchor.BN(5 * LAMPORTS_PER_SOL);
+
   // Shared test accounts
   let owner: Keypair;
   let agent: Keypair;
   let buyer: Keypair;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
   let tokenRegistry: PublicKey;
   let deskTokenTreasury: PublicKey;
   let deskUsdcTreasury: PublicKey;
+  let usdcPaymentRegistry: PublicKey;
   let ownerTokenAta: PublicKey;
+  let ownerUsdcProceeds: PublicKey;
+  let ownerSolProceeds: PublicKey;
 
   beforeEach(async () => {
     owner = Keypair.generate();
     agent = Keypair.generate();
     buyer = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
 
     await Promise.all([
       airdrop(owner.publicKey, 5 * LAMPORTS_PER_SOL),
@@ -76,12 +211,12 @@ describe("OTC Comprehensive Flow Tests", () => {
     usdcMint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
 
     // Setup desk treasuries
-    deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, desk.publicKey, true);
-    deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, desk.publicKey, true);
+    deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, treasuryAuthority, true);
+    deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, treasuryAuthority, true);
     tokenRegistry = getTokenRegistryPda(desk.publicKey, tokenMint);
 
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, desk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, desk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, treasuryAuthority, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, treasuryAuthority, true);
 
     // Initialize desk with agent
     await program.methods
@@ -92,10 +227,25 @@ describe("OTC Comprehensive Flow Tests", () => {
         agent: agent.publicKey,
         usdcMint,
         desk: desk.publicKey,
+        treasuryAuthority,
       })
       .signers([owner, desk])
       .rpc();
 
+    // Accept USDC as a pegged payment mint
+    usdcPaymentRegistry = getPaymentRegistryPda(desk.publicKey, usdcMint);
+    await program.methods
+      .registerPaymentMint(Array(32).fill(0), true)
+      .accounts({
+        desk: desk.publicKey,
+        authority: owner.publicKey,
+        paymentMint: usdcMint,
+        deskPaymentTreasury: deskUsdcTreasury,
+        paymentRegistry: usdcPaymentRegistry,
+      })
+      .signers([owner])
+      .rpc();
+
     // Register token
     await program.methods
       .registerToken(Array(32).fill(0), PublicKey.default, 0)
@@ -103,17 +253,28 @@ describe("OTC Comprehensive Flow Tests", () => {
       .signers([owner])
       .rpc();
 
+    // Open the consigner's proceeds ledgers for USDC and SOL sales
+    ownerUsdcProceeds = getConsignerProceedsPda(desk.publicKey, owner.publicKey, usdcMint);
+    ownerSolProceeds = getConsignerProceedsPda(desk.publicKey, owner.publicKey, PublicKey.default);
+    for (const [paymentMint, consignerProceeds] of [[usdcMint, ownerUsdcProceeds], [PublicKey.default, ownerSolProceeds]]) {
+      await program.methods
+        .openConsignerProceeds(paymentMint)
+        .accounts({ desk: desk.publicKey, consigner: owner.publicKey, consignerProceeds, payer: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    }
+
     // Set token price: $10 per token
     await program.methods
       .setManualTokenPrice(new anchor.BN(10 * 1e8))
-      .accounts({ tokenRegistry, desk: desk.publicKey, owner: owner.publicKey })
+      .accounts({ tokenRegistry, desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
     // Set SOL price: $100
     await program.methods
-      .setPrices(new anchor.BN(10 * 1e8), new anchor.BN(100 * 1e8), new anchor.BN(0), new anchor.BN(3600))
-      .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+      .setPrices(new anchor.BN(10 * 1e8), new anchor.BN(100 * 1e8), new anchor.BN(0))
+      .accounts({ desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
@@ -128,7 +289,7 @@ describe("OTC Comprehensive Flow Tests", () => {
   // =====================================================
   describe("Consignment Creation", () => {
     it("should create a negotiable consignment with correct parameters", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       const amount = new anchor.BN(100_000n * 10n ** 9n); // 100k tokens
 
       await program.methods
@@ -146,7 +307,14 @@ describe("OTC Comprehensive Flow Tests", () => {
           true, // is_fractionalized
           false, // is_private
           500, // max_price_volatility_bps
-          new anchor.BN(3600) // max_time_to_execute_secs
+          new anchor.BN(3600), // max_time_to_execute_secs
+          0, // vesting_cliff_days
+          0, // vesting_duration_days
+          0, // vesting_period_days
+          new anchor.BN(0), // max_quote_lifetime_secs (desk default)
+          false, // is_dutch_auction
+          new anchor.BN(0), // auction_duration_secs
+          false // is_sealed_bid
         )
         .accounts({
           desk: desk.publicKey,
@@ -154,16 +322,18 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       // Verify consignment state
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
       assert.equal(consignmentAccount.desk.toBase58(), desk.publicKey.toBase58());
+      assert.equal(consignmentAccount.id.toString(), "1"); // first id on a fresh desk
       assert.equal(consignmentAccount.totalAmount.toString(), amount.toString());
       assert.equal(consignmentAccount.remainingAmount.toString(), amount.toString());
       assert.isTrue(consignmentAccount.isNegotiable);
@@ -175,10 +345,15 @@ describe("OTC Comprehensive Flow Tests", () => {
       // Verify tokens were transferred to treasury
       const treasuryBalance = await getAccount(provider.connection, deskTokenTreasury);
       assert.equal(treasuryBalance.amount.toString(), amount.toString());
+
+      // Verify consigned inventory is tracked separately from desk-owned inventory
+      const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
+      assert.equal(registry.consignedAmount.toString(), amount.toString());
+      assert.equal(registry.deskOwnedAmount.toString(), "0");
     });
 
     it("should create a fixed-price (P2P) consignment", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       const amount = new anchor.BN(50_000n * 10n ** 9n);
 
       await program.methods
@@ -192,8 +367,11 @@ describe("OTC Comprehensive Flow Tests", () => {
           amount, // max = total for non-fractionalized
           false, // NOT fractionalized
           false,
-          0,
-          new anchor.BN(3600)
+          500,
+          new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -201,20 +379,21 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
       assert.isFalse(consignmentAccount.isNegotiable);
       assert.isFalse(consignmentAccount.isFractionalized);
     });
 
     it("should REJECT consignment with zero amount", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
 
       await expectRejectedWith(
         program.methods
@@ -222,7 +401,10 @@ describe("OTC Comprehensive Flow Tests", () => {
             new anchor.BN(0), // ZERO amount
             true, 500, 30, 100, 1000, 7, 365,
             new anchor.BN("1000000000"), new anchor.BN("1000000000000000000"),
-            true, false, 500, new anchor.BN(3600)
+            true, false, 500, new anchor.BN(3600),
+            0, 0, 0,
+            new anchor.BN(0),
+            false, new anchor.BN(0), false
           )
           .accounts({
             desk: desk.publicKey,
@@ -230,18 +412,19 @@ describe("OTC Comprehensive Flow Tests", () => {
             tokenMint,
             consignerTokenAta: ownerTokenAta,
             deskTokenTreasury,
-            consignment: consignment.publicKey,
+            tokenRegistry,
+            consignment,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, consignment])
+          .signers([owner])
           .rpc(),
         "AmountRange"
       );
     });
 
     it("should REJECT consignment with min > max deal amount", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
 
       await expectRejectedWith(
         program.methods
@@ -250,7 +433,10 @@ describe("OTC Comprehensive Flow Tests", () => {
             true, 500, 30, 100, 1000, 7, 365,
             new anchor.BN(50000 * 1e9), // min > max
             new anchor.BN(10000 * 1e9),
-            true, false, 500, new anchor.BN(3600)
+            true, false, 500, new anchor.BN(3600),
+            0, 0, 0,
+            new anchor.BN(0),
+            false, new anchor.BN(0), false
           )
           .accounts({
             desk: desk.publicKey,
@@ -258,18 +444,19 @@ describe("OTC Comprehensive Flow Tests", () => {
             tokenMint,
             consignerTokenAta: ownerTokenAta,
             deskTokenTreasury,
-            consignment: consignment.publicKey,
+            tokenRegistry,
+            consignment,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, consignment])
+          .signers([owner])
           .rpc(),
         "AmountRange"
       );
     });
 
     it("should REJECT consignment with discount > 100%", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
 
       await expectRejectedWith(
         program.methods
@@ -279,7 +466,10 @@ describe("OTC Comprehensive Flow Tests", () => {
             10001, // > 100%
             7, 365,
             new anchor.BN("1000000000"), new anchor.BN("1000000000000000000"),
-            true, false, 500, new anchor.BN(3600)
+            true, false, 500, new anchor.BN(3600),
+            0, 0, 0,
+            new anchor.BN(0),
+            false, new anchor.BN(0), false
           )
           .accounts({
             desk: desk.publicKey,
@@ -287,11 +477,12 @@ describe("OTC Comprehensive Flow Tests", () => {
             tokenMint,
             consignerTokenAta: ownerTokenAta,
             deskTokenTreasury,
-            consignment: consignment.publicKey,
+            tokenRegistry,
+            consignment,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, consignment])
+          .signers([owner])
           .rpc(),
         "Discount"
       );
@@ -300,11 +491,11 @@ describe("OTC Comprehensive Flow Tests", () => {
     it("should REJECT consignment when desk is paused", async () => {
       // Pause desk
       await program.methods.pause()
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .accounts({ desk: desk.publicKey, authority: owner.publicKey })
         .signers([owner])
         .rpc();
 
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
 
       await expectRejectedWith(
         program.methods
@@ -312,7 +503,10 @@ describe("OTC Comprehensive Flow Tests", () => {
             new anchor.BN("100000000000000"), // 100k tokens
             true, 500, 30, 100, 1000, 7, 365,
             new anchor.BN("1000000000"), new anchor.BN("1000000000000000000"),
-            true, false, 500, new anchor.BN(3600)
+            true, false, 500, new anchor.BN(3600),
+            0, 0, 0,
+            new anchor.BN(0),
+            false, new anchor.BN(0), false
           )
           .accounts({
             desk: desk.publicKey,
@@ -320,18 +514,19 @@ describe("OTC Comprehensive Flow Tests", () => {
             tokenMint,
             consignerTokenAta: ownerTokenAta,
             deskTokenTreasury,
-            consignment: consignment.publicKey,
+            tokenRegistry,
+            consignment,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, consignment])
+          .signers([owner])
           .rpc(),
         "Paused"
       );
 
       // Unpause for other tests
       await program.methods.unpause()
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .accounts({ desk: desk.publicKey, authority: owner.publicKey })
         .signers([owner])
         .rpc();
     });
@@ -341,21 +536,24 @@ describe("OTC Comprehensive Flow Tests", () => {
   // COMPLETE OTC FLOW TESTS
   // =====================================================
   describe("Complete OTC Flow - USDC Payment", () => {
-    let consignment: Keypair;
-    let offer: Keypair;
+    let consignment: PublicKey;
+    let offer: PublicKey;
     let buyerUsdcAta: PublicKey;
     let buyerTokenAta: PublicKey;
 
     beforeEach(async () => {
       // Create consignment
-      consignment = Keypair.generate();
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -363,16 +561,17 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       // Add agent as approver
-      await program.methods.setApprover(agent.publicKey, true)
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+      await program.methods.setApprover(agent.publicKey, new anchor.BN(1_000_000 * 1e8), new anchor.BN(10_000_000 * 1e8), false)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, approverAccount: getApproverPda(desk.publicKey, agent.publicKey) })
         .signers([owner])
         .rpc();
 
@@ -388,13 +587,13 @@ describe("OTC Comprehensive Flow Tests", () => {
     });
 
     it("should complete full negotiable flow: create offer → approve → fulfill → claim", async () => {
-      offer = Keypair.generate();
+      offer = await nextOfferPda();
       const tokenAmount = new anchor.BN(10_000n * 10n ** 9n); // 10k tokens
       const discountBps = 500; // 5%
       const agentCommissionBps = 50; // 0.5%
 
       // Get consignment ID
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
 
       // 1. Create offer from consignment
       await program.methods
@@ -402,23 +601,26 @@ describe("OTC Comprehensive Flow Tests", () => {
           new anchor.BN(consignmentAccount.id.toString()),
           tokenAmount,
           discountBps,
-          1, // USDC
+          usdcMint, // USDC
           new anchor.BN(0), // no lockup
-          agentCommissionBps
+          agentCommissionBps,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0), // no vesting
+          new anchor.BN(0) // longest quote lifetime allowed
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Verify offer created but not approved (negotiable)
-      let offerAccount = await program.account.offer.fetch(offer.publicKey);
+      let offerAccount = await program.account.offer.fetch(offer);
       assert.isFalse(offerAccount.approved);
       assert.equal(offerAccount.tokenAmount.toString(), tokenAmount.toString());
       assert.equal(offerAccount.discountBps, discountBps);
@@ -429,14 +631,15 @@ describe("OTC Comprehensive Flow Tests", () => {
         .approveOffer(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          consignment: consignment.publicKey,
+          offer,
+          consignment,
           approver: agent.publicKey,
+          offerApproval: getOfferApprovalPda(offer, agent.publicKey),
         })
         .signers([agent])
         .rpc();
 
-      offerAccount = await program.account.offer.fetch(offer.publicKey);
+      offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.approved);
 
       // 3. Fulfill offer with USDC
@@ -449,24 +652,28 @@ describe("OTC Comprehensive Flow Tests", () => {
       const treasuryUsdcBefore = (await getAccount(provider.connection, deskUsdcTreasury)).amount;
 
       await program.methods
-        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           deskTokenTreasury,
-          deskUsdcTreasury,
-          payerUsdcAta: buyerUsdcAta,
-          agentUsdcAta,
-          deskSigner: desk.publicKey,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: agentUsdcAta,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
-      offerAccount = await program.account.offer.fetch(offer.publicKey);
+      offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.paid);
       assert.isTrue(offerAccount.amountPaid.toNumber() > 0);
 
@@ -478,23 +685,27 @@ describe("OTC Comprehensive Flow Tests", () => {
       const agentUsdcBalance = (await getAccount(provider.connection, agentUsdcAta)).amount;
       assert.isTrue(Number(agentUsdcBalance) > 0);
 
+      // Verify sale proceeds credited to the consigner's ledger
+      const proceeds = await program.account.consignerProceeds.fetch(ownerUsdcProceeds);
+      assert.isTrue(proceeds.owed.toNumber() > 0);
+
       // 4. Claim tokens
       await program.methods
         .claim(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          deskSigner: desk.publicKey,
-          offer: offer.publicKey,
+          treasuryAuthority,
+          offer,
           tokenMint,
           deskTokenTreasury,
+          tokenRegistry,
           beneficiaryTokenAta: buyerTokenAta,
           beneficiary: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([desk])
         .rpc();
 
-      offerAccount = await program.account.offer.fetch(offer.publicKey);
+      offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.fulfilled);
 
       // Verify tokens received
@@ -504,7 +715,7 @@ describe("OTC Comprehensive Flow Tests", () => {
 
     it("should auto-approve P2P (non-negotiable) offers", async () => {
       // Create P2P consignment
-      const p2pConsignment = Keypair.generate();
+      const p2pConsignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(50_000n * 10n ** 9n),
@@ -513,7 +724,10 @@ describe("OTC Comprehensive Flow Tests", () => {
           0, 0, 0, 0, 0,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -521,39 +735,43 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: p2pConsignment.publicKey,
+          tokenRegistry,
+          consignment: p2pConsignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, p2pConsignment])
+        .signers([owner])
         .rpc();
 
-      const p2pConsignmentAccount = await program.account.consignment.fetch(p2pConsignment.publicKey);
+      const p2pConsignmentAccount = await program.account.consignment.fetch(p2pConsignment);
 
       // Create offer - should be auto-approved
-      offer = Keypair.generate();
+      offer = await nextOfferPda();
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(p2pConsignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
           300, // must match fixed discount
-          1, 
+          usdcMint,
           new anchor.BN(0), // must match fixed lockup (0)
-          0 // ignored for P2P
+          0, // ignored for P2P
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: p2pConsignment.publicKey,
+          consignment: p2pConsignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // P2P offers are auto-approved
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.approved);
     });
   });
@@ -562,10 +780,10 @@ describe("OTC Comprehensive Flow Tests", () => {
   // SOL PAYMENT FLOW
   // =====================================================
   describe("Complete OTC Flow - SOL Payment", () => {
-    let consignment: Keypair;
+    let consignment: PublicKey;
 
     beforeEach(async () => {
-      consignment = Keypair.generate();
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"), // 100k tokens
@@ -573,7 +791,10 @@ describe("OTC Comprehensive Flow Tests", () => {
           500, 0, 0, 0, 0, 0,
           new anchor.BN("10000000000"), // 10 tokens min
           new anchor.BN("50000000000000"), // 50k tokens max
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -581,42 +802,45 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
     });
 
     it("should complete SOL payment flow with balance verification", async () => {
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
       // Use smaller amount: 40 tokens * $10 * 5% discount = $380 worth
       // At $100/SOL = 3.8 SOL (affordable with 5 SOL airdrop)
       const tokenAmount = new anchor.BN("40000000000"); // 40 tokens
 
-      // Create offer with SOL payment (currency = 0)
+      // Create offer with SOL payment
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           tokenAmount,
-          500, 0, // currency = SOL
-          new anchor.BN(0), 0
+          500, PublicKey.default, // pay in SOL
+          new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
-      let offerAccount = await program.account.offer.fetch(offer.publicKey);
-      assert.equal(offerAccount.currency, 0); // SOL
+      let offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.paymentMint.equals(PublicKey.default)); // SOL
 
       // Record balances before
       const buyerSolBefore = await provider.connection.getBalance(buyer.publicKey);
@@ -624,20 +848,23 @@ describe("OTC Comprehensive Flow Tests", () => {
 
       // Fulfill with SOL
       await program.methods
-        .fulfillOfferSol(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferSol(new anchor.BN(offerAccount.id.toString()), MAX_SOL_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
+          offer,
           deskTokenTreasury,
+          tokenRegistry,
           agent: null, // no commission for P2P in this test
-          deskSigner: desk.publicKey,
+          consignment,
+          consignerProceeds: ownerSolProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
-      offerAccount = await program.account.offer.fetch(offer.publicKey);
+      offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.paid);
 
       // Verify SOL transferred
@@ -655,15 +882,15 @@ describe("OTC Comprehensive Flow Tests", () => {
         .claim(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          deskSigner: desk.publicKey,
-          offer: offer.publicKey,
+          treasuryAuthority,
+          offer,
           tokenMint,
           deskTokenTreasury,
+          tokenRegistry,
           beneficiaryTokenAta: buyerTokenAta,
           beneficiary: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([desk])
         .rpc();
 
       const buyerTokenBalance = (await getAccount(provider.connection, buyerTokenAta)).amount;
@@ -675,18 +902,21 @@ describe("OTC Comprehensive Flow Tests", () => {
   // CANCEL FLOW TESTS
   // =====================================================
   describe("Cancel Offer Flows", () => {
-    let consignment: Keypair;
-    let offer: Keypair;
+    let consignment: PublicKey;
+    let offer: PublicKey;
 
     beforeEach(async () => {
-      consignment = Keypair.generate();
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -694,39 +924,43 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       // Add agent as approver
-      await program.methods.setApprover(agent.publicKey, true)
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+      await program.methods.setApprover(agent.publicKey, new anchor.BN(1_000_000 * 1e8), new anchor.BN(10_000_000 * 1e8), false)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, approverAccount: getApproverPda(desk.publicKey, agent.publicKey) })
         .signers([owner])
         .rpc();
     });
 
     it("should allow owner to cancel unapproved offer", async () => {
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 50
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Owner cancels
@@ -734,18 +968,18 @@ describe("OTC Comprehensive Flow Tests", () => {
         .cancelOfferWithConsignment()
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          consignment: consignment.publicKey,
+          offer,
+          consignment,
           caller: owner.publicKey,
         })
         .signers([owner])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.cancelled);
 
       // Verify tokens restored to consignment
-      const consignmentAfter = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAfter = await program.account.consignment.fetch(consignment);
       assert.equal(
         consignmentAfter.remainingAmount.toString(),
         consignmentAccount.remainingAmount.toString()
@@ -753,24 +987,27 @@ describe("OTC Comprehensive Flow Tests", () => {
     });
 
     it("should allow agent to cancel offer", async () => {
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 50
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Agent cancels
@@ -778,36 +1015,39 @@ describe("OTC Comprehensive Flow Tests", () => {
         .cancelOfferWithConsignment()
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          consignment: consignment.publicKey,
+          offer,
+          consignment,
           caller: agent.publicKey,
         })
         .signers([agent])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       assert.isTrue(offerAccount.cancelled);
     });
 
     it("should REJECT buyer cancel before expiry", async () => {
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 50
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Buyer tries to cancel before expiry
@@ -816,8 +1056,8 @@ describe("OTC Comprehensive Flow Tests", () => {
           .cancelOfferWithConsignment()
           .accounts({
             desk: desk.publicKey,
-            offer: offer.publicKey,
-            consignment: consignment.publicKey,
+            offer,
+            consignment,
             caller: buyer.publicKey,
           })
           .signers([buyer])
@@ -825,6 +1065,132 @@ describe("OTC Comprehensive Flow Tests", () => {
         "NotExpired"
       );
     });
+
+    it("should keep a withdrawn consignment inactive when its open offer is cancelled", async () => {
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const consignmentId = new anchor.BN(consignmentAccount.id.toString());
+      const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
+      offer = await nextOfferPda();
+
+      await program.methods
+        .createOfferFromConsignment(
+          consignmentId,
+          tokenAmount,
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await program.methods
+        .withdrawConsignment(consignmentId)
+        .accounts({
+          consignment,
+          desk: desk.publicKey,
+          tokenMint,
+          treasuryAuthority,
+          consigner: owner.publicKey,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignerTokenAta: ownerTokenAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+
+      await program.methods
+        .cancelOfferWithConsignment()
+        .accounts({
+          desk: desk.publicKey,
+          offer,
+          consignment,
+          caller: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      // The offer's tokens come back for withdrawal, but the consignment is not sellable again
+      const consignmentAfter = await program.account.consignment.fetch(consignment);
+      assert.isFalse(consignmentAfter.isActive);
+      assert.equal(consignmentAfter.remainingAmount.toString(), tokenAmount.toString());
+    });
+
+    it("should put a sold-out consignment back on sale when its offer is cancelled", async () => {
+      const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
+      const soldOut = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          tokenAmount,
+          true, 500, 0, 100, 1000, 0, 365,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment: soldOut,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+      const consignmentAccount = await program.account.consignment.fetch(soldOut);
+      offer = await nextOfferPda();
+
+      await program.methods
+        .createOfferFromConsignment(
+          new anchor.BN(consignmentAccount.id.toString()),
+          tokenAmount,
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment: soldOut,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.isFalse((await program.account.consignment.fetch(soldOut)).isActive);
+
+      await program.methods
+        .cancelOfferWithConsignment()
+        .accounts({
+          desk: desk.publicKey,
+          offer,
+          consignment: soldOut,
+          caller: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      const consignmentAfter = await program.account.consignment.fetch(soldOut);
+      assert.isTrue(consignmentAfter.isActive);
+      assert.equal(consignmentAfter.remainingAmount.toString(), tokenAmount.toString());
+    });
   });
 
   // =====================================================
@@ -859,12 +1225,12 @@ describe("OTC Comprehensive Flow Tests", () => {
           desk: desk.publicKey,
           tokenRegistry,
           tokenMint,
-          deskSigner: desk.publicKey,
+          treasuryAuthority,
           deskTokenTreasury,
           ownerTokenAta,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([owner, desk])
+        .signers([owner])
         .rpc();
 
       const ownerBalanceAfter = (await getAccount(provider.connection, ownerTokenAta)).amount;
@@ -889,12 +1255,11 @@ describe("OTC Comprehensive Flow Tests", () => {
           .withdrawSol(new anchor.BN(withdrawable))
           .accounts({
             desk: desk.publicKey,
-            deskSigner: desk.publicKey,
             owner: owner.publicKey,
             to: owner.publicKey,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, desk])
+          .signers([owner])
           .rpc();
 
         const deskBalanceAfter = await provider.connection.getBalance(desk.publicKey);
@@ -910,12 +1275,11 @@ describe("OTC Comprehensive Flow Tests", () => {
           .withdrawSol(new anchor.BN(deskBalance)) // Try to withdraw all
           .accounts({
             desk: desk.publicKey,
-            deskSigner: desk.publicKey,
             owner: owner.publicKey,
             to: owner.publicKey,
             systemProgram: SystemProgram.programId,
           })
-          .signers([owner, desk])
+          .signers([owner])
           .rpc(),
         "BadState"
       );
@@ -927,7 +1291,7 @@ describe("OTC Comprehensive Flow Tests", () => {
   // =====================================================
   describe("Consignment Withdrawal", () => {
     it("should allow consigner to withdraw remaining tokens", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       const consignAmount = new anchor.BN(100_000n * 10n ** 9n);
 
       await program.methods
@@ -936,7 +1300,10 @@ describe("OTC Comprehensive Flow Tests", () => {
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -944,33 +1311,35 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       const ownerBalanceBefore = (await getAccount(provider.connection, ownerTokenAta)).amount;
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
 
       await program.methods
         .withdrawConsignment(new anchor.BN(consignmentAccount.id.toString()))
         .accounts({
-          consignment: consignment.publicKey,
+          consignment,
           desk: desk.publicKey,
           tokenMint,
-          deskSigner: desk.publicKey,
+          treasuryAuthority,
           consigner: owner.publicKey,
           deskTokenTreasury,
+          tokenRegistry,
           consignerTokenAta: ownerTokenAta,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([owner, desk])
+        .signers([owner])
         .rpc();
 
       // Verify consignment deactivated
-      const consignmentAfter = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAfter = await program.account.consignment.fetch(consignment);
       assert.isFalse(consignmentAfter.isActive);
       assert.equal(consignmentAfter.remainingAmount.toString(), "0");
 
@@ -983,7 +1352,7 @@ describe("OTC Comprehensive Flow Tests", () => {
     });
 
     it("should REJECT withdrawal by non-consigner", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
 
       await program.methods
         .createConsignment(
@@ -991,7 +1360,10 @@ describe("OTC Comprehensive Flow Tests", () => {
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -999,14 +1371,15 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
       const buyerTokenAta = (await getOrCreateAssociatedTokenAccount(
         provider.connection, buyer, tokenMint, buyer.publicKey
       )).address;
@@ -1015,16 +1388,17 @@ describe("OTC Comprehensive Flow Tests", () => {
         program.methods
           .withdrawConsignment(new anchor.BN(consignmentAccount.id.toString()))
           .accounts({
-            consignment: consignment.publicKey,
+            consignment,
             desk: desk.publicKey,
             tokenMint,
-            deskSigner: desk.publicKey,
+            treasuryAuthority,
             consigner: buyer.publicKey, // Wrong consigner
             deskTokenTreasury,
+            tokenRegistry,
             consignerTokenAta: buyerTokenAta,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .signers([buyer, desk])
+          .signers([buyer])
           .rpc(),
         "NotOwner"
       );
@@ -1037,14 +1411,17 @@ describe("OTC Comprehensive Flow Tests", () => {
   describe("P2P Commission", () => {
     it("should verify commission range validation in offers", async () => {
       // Create negotiable consignment
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"),
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN("1000000000000"),
           new anchor.BN("50000000000000"),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1052,15 +1429,16 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       // Should REJECT commission below 25 bps for negotiable offers
       await expectRejectedWith(
@@ -1068,32 +1446,38 @@ describe("OTC Comprehensive Flow Tests", () => {
           .createOfferFromConsignment(
             new anchor.BN(consignmentAccount.id.toString()),
             new anchor.BN("10000000000000"),
-            500, 1, new anchor.BN(0),
-            10 // Too low - min is 25 bps
+            500, usdcMint, new anchor.BN(0),
+            10, // Too low - min is 25 bps
+            new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+            new anchor.BN(0)
           )
           .accounts({
             desk: desk.publicKey,
-            consignment: consignment.publicKey,
+            consignment,
             tokenRegistry,
+            paymentRegistry: usdcPaymentRegistry,
             beneficiary: buyer.publicKey,
-            offer: offer.publicKey,
+            offer,
             systemProgram: SystemProgram.programId,
           })
-          .signers([buyer, offer])
+          .signers([buyer])
           .rpc(),
         "CommissionRange"
       );
     });
 
     it("should REJECT commission above 150 bps for negotiable offers", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"),
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN("1000000000000"),
           new anchor.BN("50000000000000"),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1101,15 +1485,16 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       // Should REJECT commission above 150 bps
       await expectRejectedWith(
@@ -1117,18 +1502,21 @@ describe("OTC Comprehensive Flow Tests", () => {
           .createOfferFromConsignment(
             new anchor.BN(consignmentAccount.id.toString()),
             new anchor.BN("10000000000000"),
-            500, 1, new anchor.BN(0),
-            200 // Too high - max is 150 bps
+            500, usdcMint, new anchor.BN(0),
+            200, // Too high - max is 150 bps
+            new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+            new anchor.BN(0)
           )
           .accounts({
             desk: desk.publicKey,
-            consignment: consignment.publicKey,
+            consignment,
             tokenRegistry,
+            paymentRegistry: usdcPaymentRegistry,
             beneficiary: buyer.publicKey,
-            offer: offer.publicKey,
+            offer,
             systemProgram: SystemProgram.programId,
           })
-          .signers([buyer, offer])
+          .signers([buyer])
           .rpc(),
         "CommissionRange"
       );
@@ -1140,7 +1528,7 @@ describe("OTC Comprehensive Flow Tests", () => {
   // =====================================================
   describe("Lockup Period Enforcement", () => {
     it("should REJECT claim before unlock time", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
@@ -1149,7 +1537,10 @@ describe("OTC Comprehensive Flow Tests", () => {
           0, 0, 0, 0,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1157,34 +1548,38 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       // Create and fulfill offer
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, 
+          500, usdcMint,
           new anchor.BN(86400), // 1 day lockup
-          0
+          0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Setup and pay
@@ -1193,24 +1588,28 @@ describe("OTC Comprehensive Flow Tests", () => {
       )).address;
       await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, 1_000_000n * 10n ** 6n);
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
 
       await program.methods
-        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           deskTokenTreasury,
-          deskUsdcTreasury,
-          payerUsdcAta: buyerUsdcAta,
-          agentUsdcAta: null,
-          deskSigner: desk.publicKey,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
       // Try to claim immediately (should fail due to lockup)
@@ -1223,15 +1622,15 @@ describe("OTC Comprehensive Flow Tests", () => {
           .claim(new anchor.BN(offerAccount.id.toString()))
           .accounts({
             desk: desk.publicKey,
-            deskSigner: desk.publicKey,
-            offer: offer.publicKey,
+            treasuryAuthority,
+            offer,
             tokenMint,
             deskTokenTreasury,
+            tokenRegistry,
             beneficiaryTokenAta: buyerTokenAta,
             beneficiary: buyer.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .signers([desk])
           .rpc(),
         "Locked"
       );
@@ -1239,31 +1638,27 @@ describe("OTC Comprehensive Flow Tests", () => {
   });
 
   // =====================================================
-  // BOUNDARY CONDITIONS
+  // VESTING
   // =====================================================
-  describe("Boundary Conditions", () => {
-    it("should handle minimum USD amount boundary", async () => {
-      // Set very low min USD ($0.01)
-      await program.methods
-        .setLimits(
-          new anchor.BN("1000000"), // $0.01 with 8 decimals
-          new anchor.BN("1000000000000000000"),
-          new anchor.BN(60),
-          new anchor.BN(0),
-          new anchor.BN(365 * 86400)
-        )
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
-        .signers([owner])
-        .rpc();
+  describe("Vesting", () => {
+    let consignment: PublicKey;
+    let buyerUsdcAta: PublicKey;
+    let buyerTokenAta: PublicKey;
+    const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
 
-      const consignment = Keypair.generate();
+    beforeEach(async () => {
+      // Negotiable consignment allowing up to a 1 day cliff over 2 days, vesting daily
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
-          new anchor.BN("1000000000"), // 1 token
-          false, 0, 0, 0, 0, 0, 0,
-          new anchor.BN(1), // min 1 unit
-          new anchor.BN("1000000000"),
-          true, false, 0, new anchor.BN(3600)
+          new anchor.BN(100_000n * 10n ** 9n),
+          true, 500, 0, 100, 1000, 0, 365,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          1, 2, 1,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1271,60 +1666,960 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      // Should succeed with small amount
-      const offer = Keypair.generate();
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
+      buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer, usdcMint, buyer.publicKey
+      )).address;
+      await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, 1_000_000n * 10n ** 6n);
 
-      await program.methods
-        .createOfferFromConsignment(
+      buyerTokenAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer, tokenMint, buyer.publicKey
+      )).address;
+    });
+
+    // Offer with no lockup, so vesting starts as soon as the offer is created
+    const createVestingOffer = async (cliffSecs: number, durationSecs: number, periodSecs: number) => {
+      const offer = await nextOfferPda();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      await program.methods
+        .createOfferFromConsignment(
+          new anchor.BN(consignmentAccount.id.toString()),
+          tokenAmount,
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(cliffSecs), new anchor.BN(durationSecs), new anchor.BN(periodSecs),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      return offer;
+    };
+
+    const approveAndPay = async (offer: PublicKey) => {
+      const offerId = (await program.account.offer.fetch(offer)).id;
+      await program.methods
+        .approveOffer(offerId)
+        .accounts({
+          desk: desk.publicKey,
+          offer,
+          consignment,
+          approver: agent.publicKey,
+          offerApproval: getOfferApprovalPda(offer, agent.publicKey),
+        })
+        .signers([agent])
+        .rpc();
+      await program.methods
+        .fulfillOfferUsdc(offerId, MAX_USDC_PAYMENT)
+        .accounts({
+          desk: desk.publicKey,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskTokenTreasury,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
+          payer: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+    };
+
+    const claimVested = async (offer: PublicKey) => {
+      const offerId = (await program.account.offer.fetch(offer)).id;
+      return program.methods
+        .claim(offerId)
+        .accounts({
+          desk: desk.publicKey,
+          treasuryAuthority,
+          offer,
+          tokenMint,
+          deskTokenTreasury,
+          tokenRegistry,
+          beneficiaryTokenAta: buyerTokenAta,
+          beneficiary: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    };
+
+    it("should release tokens in period steps after the cliff", async () => {
+      // 4s cliff, then 2s steps until fully vested at 10s
+      const offer = await createVestingOffer(4, 10, 2);
+      await approveAndPay(offer);
+      const { vestingStart } = await program.account.offer.fetch(offer);
+
+      // Nothing is claimable inside the cliff
+      await expectRejectedWith(claimVested(offer), "Locked");
+
+      await waitForChainTime(vestingStart.toNumber() + 5);
+      await claimVested(offer);
+      let offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.claimedAmount.gtn(0));
+      assert.isTrue(offerAccount.claimedAmount.lt(tokenAmount));
+      assert.isFalse(offerAccount.fulfilled);
+      let buyerTokenBalance = (await getAccount(provider.connection, buyerTokenAta)).amount;
+      assert.equal(buyerTokenBalance.toString(), offerAccount.claimedAmount.toString());
+
+      await waitForChainTime(vestingStart.toNumber() + 10);
+      await claimVested(offer);
+      offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.fulfilled);
+      buyerTokenBalance = (await getAccount(provider.connection, buyerTokenAta)).amount;
+      assert.equal(buyerTokenBalance.toString(), tokenAmount.toString());
+    });
+
+    it("should REJECT vesting longer than the consignment allows", async () => {
+      await expectRejectedWith(createVestingOffer(0, 3 * 86400, 86400), "InvalidVesting");
+    });
+
+    it("should REJECT a vesting period longer than the duration", async () => {
+      await expectRejectedWith(createVestingOffer(0, 10, 20), "InvalidVesting");
+    });
+
+    it("should REJECT a vesting duration without a period", async () => {
+      await expectRejectedWith(createVestingOffer(0, 10, 0), "InvalidVesting");
+    });
+  });
+
+  // =====================================================
+  // AGENT QUOTES
+  // =====================================================
+  describe("Agent Quotes", () => {
+    let consignment: PublicKey;
+
+    beforeEach(async () => {
+      consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN(100_000n * 10n ** 9n),
+          true, 500, 0, 100, 1000, 0, 365,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    const buildQuote = async (nonce: anchor.BN): Promise<anchor.IdlTypes<Otc>["agentQuote"]> => {
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      return {
+        desk: desk.publicKey,
+        consignmentId: consignmentAccount.id,
+        beneficiary: buyer.publicKey,
+        terms: {
+          tokenAmount: new anchor.BN(10_000n * 10n ** 9n),
+          discountBps: 500,
+          paymentMint: usdcMint,
+          lockupSecs: new anchor.BN(0),
+          agentCommissionBps: 50,
+          vestingCliffSecs: new anchor.BN(0),
+          vestingDurationSecs: new anchor.BN(0),
+          vestingPeriodSecs: new anchor.BN(0),
+          quoteLifetimeSecs: new anchor.BN(0),
+        },
+        expiresAt: new anchor.BN(Math.floor(Date.now() / 1000) + 600),
+        nonce,
+      };
+    };
+
+    // The program reads the ed25519 verification from the instruction right before it
+    const redeemQuote = async (quote: anchor.IdlTypes<Otc>["agentQuote"], signer: Keypair) => {
+      const offer = await nextOfferPda();
+      const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: program.coder.types.encode("agentQuote", quote),
+      });
+      await program.methods
+        .createOfferFromQuote(quote)
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          quoteNonce: getQuoteNoncePda(desk.publicKey, quote.nonce),
+          offerApproval: getOfferApprovalPda(offer, agent.publicKey),
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions([verifyIx])
+        .signers([buyer])
+        .rpc();
+      return offer;
+    };
+
+    it("should open a pre-approved offer from an agent-signed quote", async () => {
+      const nonce = new anchor.BN(1);
+      const offer = await redeemQuote(await buildQuote(nonce), agent);
+
+      const offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.approved);
+      assert.equal(offerAccount.beneficiary.toBase58(), buyer.publicKey.toBase58());
+      assert.equal(offerAccount.discountBps, 500);
+
+      const quoteNonce = await program.account.quoteNonce.fetch(getQuoteNoncePda(desk.publicKey, nonce));
+      assert.equal(quoteNonce.offer.toBase58(), offer.toBase58());
+    });
+
+    it("should REJECT redeeming a quote nonce twice", async () => {
+      const quote = await buildQuote(new anchor.BN(2));
+      await redeemQuote(quote, agent);
+      await expectRejectedWith(redeemQuote(quote, agent), "already in use");
+    });
+
+    it("should REJECT a quote not signed by the agent", async () => {
+      await expectRejectedWith(
+        redeemQuote(await buildQuote(new anchor.BN(3)), buyer),
+        "InvalidQuoteSignature"
+      );
+    });
+
+    it("should REJECT an expired quote", async () => {
+      const quote = await buildQuote(new anchor.BN(4));
+      quote.expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) - 600);
+      await expectRejectedWith(redeemQuote(quote, agent), "Expired");
+    });
+
+    it("should REJECT the agent approving its own quoted offer a second time", async () => {
+      // Two approvals needed, so the quote alone leaves the offer unapproved
+      await program.methods
+        .setRequiredApprovals(2, new anchor.BN(0), 2)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const offer = await redeemQuote(await buildQuote(new anchor.BN(5)), agent);
+      const offerAccount = await program.account.offer.fetch(offer);
+      assert.isFalse(offerAccount.approved);
+      assert.equal(offerAccount.approvalCount, 1);
+
+      await expectRejectedWith(
+        program.methods
+          .approveOffer(offerAccount.id)
+          .accounts({
+            desk: desk.publicKey,
+            offer,
+            consignment,
+            approver: agent.publicKey,
+            offerApproval: getOfferApprovalPda(offer, agent.publicKey),
+          })
+          .signers([agent])
+          .rpc(),
+        "already in use"
+      );
+    });
+  });
+
+  // =====================================================
+  // PARTIAL FILLS
+  // =====================================================
+  describe("Partial Fills", () => {
+    let consignment: PublicKey;
+    let offer: PublicKey;
+    let offerId: anchor.BN;
+    let secondBuyer: Keypair;
+    const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
+
+    const getOfferFillPda = (offer: PublicKey, payer: PublicKey) => {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("fill"), offer.toBuffer(), payer.toBuffer()],
+        program.programId
+      )[0];
+    };
+
+    beforeEach(async () => {
+      secondBuyer = Keypair.generate();
+      await airdrop(secondBuyer.publicKey, 2 * LAMPORTS_PER_SOL);
+
+      // Non-negotiable (auto-approved) offer for 10k tokens
+      consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN(100_000n * 10n ** 9n),
+          false, 500, 0, 0, 0, 0, 0,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
+      await program.methods
+        .createOfferFromConsignment(
+          new anchor.BN(consignmentAccount.id.toString()),
+          tokenAmount,
+          500, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      offerId = (await program.account.offer.fetch(offer)).id;
+    });
+
+    const fillUsdc = async (payer: Keypair, amount: anchor.BN) => {
+      const payerUsdcAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, payer, usdcMint, payer.publicKey
+      )).address;
+      await mintTo(provider.connection, owner, usdcMint, payerUsdcAta, owner, 1_000_000n * 10n ** 6n);
+      return program.methods
+        .fillOfferUsdc(offerId, amount, MAX_USDC_PAYMENT)
+        .accounts({
+          desk: desk.publicKey,
+          offer,
+          offerFill: getOfferFillPda(offer, payer.publicKey),
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: payerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
+          payer: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc();
+    };
+
+    const syndicate = () =>
+      program.methods
+        .setOfferSyndicated(offerId, true)
+        .accounts({ desk: desk.publicKey, offer, beneficiary: buyer.publicKey })
+        .signers([buyer])
+        .rpc();
+
+    it("should split an offer between fillers and deliver each their tranche", async () => {
+      const firstTranche = new anchor.BN(4_000n * 10n ** 9n);
+      const secondTranche = tokenAmount.sub(firstTranche);
+
+      await syndicate();
+      await fillUsdc(buyer, firstTranche);
+      let offerAccount = await program.account.offer.fetch(offer);
+      assert.isFalse(offerAccount.paid);
+      assert.equal(offerAccount.filledAmount.toString(), firstTranche.toString());
+
+      await fillUsdc(secondBuyer, secondTranche);
+      offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.paid);
+      assert.equal(offerAccount.fillCount, 2);
+
+      // Claims are permissionless; tokens always go to the filler
+      for (const [payer, tranche] of [[buyer, firstTranche], [secondBuyer, secondTranche]] as const) {
+        const payerTokenAta = (await getOrCreateAssociatedTokenAccount(
+          provider.connection, payer, tokenMint, payer.publicKey
+        )).address;
+        await program.methods
+          .claimFill(offerId)
+          .accounts({
+            desk: desk.publicKey,
+            treasuryAuthority,
+            offer,
+            offerFill: getOfferFillPda(offer, payer.publicKey),
+            tokenMint,
+            deskTokenTreasury,
+            tokenRegistry,
+            payerTokenAta,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        const balance = (await getAccount(provider.connection, payerTokenAta)).amount;
+        assert.equal(balance.toString(), tranche.toString());
+      }
+
+      offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.fulfilled);
+    });
+
+    it("should REJECT a fill larger than what is left", async () => {
+      await syndicate();
+      await fillUsdc(buyer, new anchor.BN(4_000n * 10n ** 9n));
+      await expectRejectedWith(fillUsdc(secondBuyer, tokenAmount), "AmountRange");
+    });
+
+    it("should REJECT a stranger's fill unless the beneficiary opted in to syndication", async () => {
+      await expectRejectedWith(fillUsdc(secondBuyer, new anchor.BN(4_000n * 10n ** 9n)), "FulfillRestricted");
+      const offerAccount = await program.account.offer.fetch(offer);
+      assert.equal(offerAccount.filledAmount.toString(), "0");
+    });
+
+    it("should REJECT syndication set by anyone but the beneficiary", async () => {
+      await expectRejectedWith(
+        program.methods
+          .setOfferSyndicated(offerId, true)
+          .accounts({ desk: desk.publicKey, offer, beneficiary: secondBuyer.publicKey })
+          .signers([secondBuyer])
+          .rpc(),
+        "ConstraintHasOne"
+      );
+    });
+  });
+
+  // =====================================================
+  // DUTCH AUCTIONS
+  // =====================================================
+  describe("Dutch Auctions", () => {
+    // Dutch listings are P2P; the discount rises from min to max over the auction
+    const createDutchConsignment = async (isNegotiable: boolean, auctionDurationSecs: number) => {
+      const consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN(100_000n * 10n ** 9n),
+          isNegotiable, 0, 0, 100, 1000, 0, 0,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          true, new anchor.BN(auctionDurationSecs), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+      return consignment;
+    };
+
+    // The requested discount is ignored; the auction clock sets it
+    const createDutchOffer = async (consignment: PublicKey) => {
+      const offer = await nextOfferPda();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      await program.methods
+        .createOfferFromConsignment(
+          new anchor.BN(consignmentAccount.id.toString()),
+          new anchor.BN(10_000n * 10n ** 9n),
+          0, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      return program.account.offer.fetch(offer);
+    };
+
+    it("should raise the discount over the auction and cap it at the max", async () => {
+      const consignment = await createDutchConsignment(false, 6);
+
+      const early = await createDutchOffer(consignment);
+      assert.isTrue(early.approved);
+      assert.isAtLeast(early.discountBps, 100);
+      assert.isBelow(early.discountBps, 1000);
+
+      const { createdAt } = await program.account.consignment.fetch(consignment);
+      await waitForChainTime(createdAt.toNumber() + 6);
+      const late = await createDutchOffer(consignment);
+      assert.equal(late.discountBps, 1000);
+    });
+
+    it("should REJECT a negotiable Dutch auction", async () => {
+      await expectRejectedWith(createDutchConsignment(true, 60), "BadState");
+    });
+
+    it("should REJECT a Dutch auction without a duration", async () => {
+      await expectRejectedWith(createDutchConsignment(false, 0), "AmountRange");
+    });
+  });
+
+  // =====================================================
+  // SEALED-BID AUCTIONS
+  // =====================================================
+  describe("Sealed-Bid Auctions", () => {
+    let consignment: PublicKey;
+    let consignmentId: anchor.BN;
+    let auction: PublicKey;
+    let sealedBid: PublicKey;
+    let buyerUsdcAta: PublicKey;
+    const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
+    const depositAmount = new anchor.BN(100 * 1e6);
+
+    // Commitment layout matches the program: sha256(discount_bps LE, token_amount LE, salt, bidder)
+    const sealedBidCommitment = (discountBps: number, amount: anchor.BN, salt: Buffer, bidder: PublicKey) => {
+      const discount = Buffer.alloc(2);
+      discount.writeUInt16LE(discountBps);
+      return Array.from(
+        createHash("sha256")
+          .update(Buffer.concat([discount, amount.toArrayLike(Buffer, "le", 8), salt, bidder.toBuffer()]))
+          .digest()
+      );
+    };
+
+    const createSealedConsignment = async (isSealedBid: boolean) => {
+      const next = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          tokenAmount,
+          false, 0, 0, 100, 1000, 0, 0,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), isSealedBid
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment: next,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+      return next;
+    };
+
+    const openAuction = (bidWindowSecs: number, revealWindowSecs: number) =>
+      program.methods
+        .openSealedBidAuction(consignmentId, new anchor.BN(bidWindowSecs), new anchor.BN(revealWindowSecs), depositAmount)
+        .accounts({ desk: desk.publicKey, consignment, auction, consigner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    const revealBid = (discountBps: number, amount: anchor.BN, salt: Buffer) =>
+      program.methods
+        .revealSealedBid(consignmentId, discountBps, amount, Array.from(salt))
+        .accounts({ desk: desk.publicKey, consignment, auction, sealedBid, bidder: buyer.publicKey })
+        .signers([buyer])
+        .rpc();
+
+    const settleBid = (offer: PublicKey) =>
+      program.methods
+        .settleSealedBid(consignmentId)
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          auction,
+          sealedBid,
+          bidder: buyer.publicKey,
+          tokenRegistry,
+          usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          bidderUsdcAta: buyerUsdcAta,
+          treasuryAuthority,
+          offer,
+          payer: owner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+    beforeEach(async () => {
+      consignment = await createSealedConsignment(true);
+      consignmentId = (await program.account.consignment.fetch(consignment)).id;
+      auction = PublicKey.findProgramAddressSync([Buffer.from("auction"), consignment.toBuffer()], program.programId)[0];
+      sealedBid = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.toBuffer(), buyer.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+      buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer, usdcMint, buyer.publicKey
+      )).address;
+      await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, 1_000_000n * 10n ** 6n);
+    });
+
+    it("should award a revealed bid an approved offer after the reveal window", async () => {
+      await openAuction(5, 5);
+      const { bidEnd, revealEnd } = await program.account.sealedBidAuction.fetch(auction);
+
+      const salt = Buffer.alloc(32, 7);
+      await program.methods
+        .commitSealedBid(consignmentId, sealedBidCommitment(500, tokenAmount, salt, buyer.publicKey))
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          auction,
+          sealedBid,
+          usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          bidderUsdcAta: buyerUsdcAta,
+          bidder: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          approverAccount: null,
+        })
+        .signers([buyer])
+        .rpc();
+
+      // Bids stay sealed until the bid window closes
+      await expectRejectedWith(revealBid(500, tokenAmount, salt), "NotExpired");
+
+      await waitForChainTime(bidEnd.toNumber());
+      await expectRejectedWith(revealBid(400, tokenAmount, salt), "InvalidBidReveal");
+      await revealBid(500, tokenAmount, salt);
+
+      const offer = await nextOfferPda();
+      await expectRejectedWith(settleBid(offer), "NotExpired");
+
+      await waitForChainTime(revealEnd.toNumber());
+      await settleBid(offer);
+
+      const offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.approved);
+      assert.equal(offerAccount.beneficiary.toBase58(), buyer.publicKey.toBase58());
+      assert.equal(offerAccount.tokenAmount.toString(), tokenAmount.toString());
+      assert.equal(offerAccount.discountBps, 500);
+      assert.equal(offerAccount.awardDeposit.toString(), depositAmount.toString());
+      assert.isNull(await provider.connection.getAccountInfo(sealedBid));
+    });
+
+    it("should REJECT opening an auction on a regular consignment", async () => {
+      consignment = await createSealedConsignment(false);
+      consignmentId = (await program.account.consignment.fetch(consignment)).id;
+      auction = PublicKey.findProgramAddressSync([Buffer.from("auction"), consignment.toBuffer()], program.programId)[0];
+      await expectRejectedWith(openAuction(60, 60), "BadState");
+    });
+
+    it("should allow a second auction once the first one finishes", async () => {
+      await openAuction(2, 2);
+      const { revealEnd } = await program.account.sealedBidAuction.fetch(auction);
+      await waitForChainTime(revealEnd.toNumber());
+
+      // No bids were committed, so the whole block goes back to the consignment
+      await program.methods
+        .finishSealedBidAuction(consignmentId)
+        .accounts({ desk: desk.publicKey, consignment, auction, consigner: owner.publicKey })
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(auction));
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      assert.equal(consignmentAccount.remainingAmount.toString(), tokenAmount.toString());
+
+      await openAuction(60, 60);
+      const reopened = await program.account.sealedBidAuction.fetch(auction);
+      assert.equal(reopened.tokenAmount.toString(), tokenAmount.toString());
+      assert.equal(reopened.commitCount, 0);
+    });
+  });
+
+  // =====================================================
+  // RFQ BIDS
+  // =====================================================
+  describe("RFQ Bids", () => {
+    let consignment: PublicKey;
+    let consignmentId: anchor.BN;
+    let buyerUsdcAta: PublicKey;
+    const tokenAmount = new anchor.BN(10_000n * 10n ** 9n);
+    const escrowAmount = new anchor.BN(100_000 * 1e6);
+    const buyerUsdcFunding = 1_000_000n * 10n ** 6n;
+
+    const getRfqBidPda = (bidder: PublicKey, bidId: anchor.BN) => {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("rfq_bid"), desk.publicKey.toBuffer(), bidder.toBuffer(), bidId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    };
+
+    beforeEach(async () => {
+      // P2P consignment at a fixed 5% discount with no lockup
+      consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN(100_000n * 10n ** 9n),
+          false, 500, 0, 0, 0, 0, 0,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+      consignmentId = (await program.account.consignment.fetch(consignment)).id;
+
+      buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer, usdcMint, buyer.publicKey
+      )).address;
+      await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, buyerUsdcFunding);
+    });
+
+    const placeBid = async (bidId: anchor.BN, maxDiscountBps: number) => {
+      const bid = getRfqBidPda(buyer.publicKey, bidId);
+      await program.methods
+        .placeBidUsdc(bidId, tokenAmount, maxDiscountBps, new anchor.BN(0), escrowAmount)
+        .accounts({
+          desk: desk.publicKey,
+          tokenRegistry,
+          bid,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          bidderPaymentAta: buyerUsdcAta,
+          bidder: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      return bid;
+    };
+
+    const hitBid = (bid: PublicKey, offer: PublicKey, discountBps: number) =>
+      program.methods
+        .hitBidUsdc(consignmentId, discountBps, new anchor.BN(0))
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          bid,
+          bidder: buyer.publicKey,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          bidderPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
+          offer,
+          consigner: owner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+    it("should sell into a bid as a paid offer and refund unused escrow", async () => {
+      const bid = await placeBid(new anchor.BN(1), 800);
+      let buyerUsdc = (await getAccount(provider.connection, buyerUsdcAta)).amount;
+      assert.equal(buyerUsdc.toString(), (buyerUsdcFunding - BigInt(escrowAmount.toString())).toString());
+
+      const offer = await nextOfferPda();
+      await hitBid(bid, offer, 500);
+
+      const offerAccount = await program.account.offer.fetch(offer);
+      assert.isTrue(offerAccount.approved);
+      assert.isTrue(offerAccount.paid);
+      assert.equal(offerAccount.beneficiary.toBase58(), buyer.publicKey.toBase58());
+      assert.equal(offerAccount.tokenAmount.toString(), tokenAmount.toString());
+      assert.isNull(await provider.connection.getAccountInfo(bid));
+
+      // 10k tokens * $10 * (1 - 5%) = $95,000 kept; the rest of the escrow comes back
+      buyerUsdc = (await getAccount(provider.connection, buyerUsdcAta)).amount;
+      assert.equal(buyerUsdc.toString(), (buyerUsdcFunding - 95_000n * 10n ** 6n).toString());
+    });
+
+    it("should REJECT hitting a bid above its max discount", async () => {
+      const bid = await placeBid(new anchor.BN(2), 400);
+      await expectRejectedWith(hitBid(bid, await nextOfferPda(), 500), "Discount");
+    });
+
+    it("should refund the escrow when the bidder cancels", async () => {
+      const bidId = new anchor.BN(3);
+      const bid = await placeBid(bidId, 800);
+
+      await program.methods
+        .cancelBidUsdc(bidId)
+        .accounts({
+          desk: desk.publicKey,
+          bid,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          bidderPaymentAta: buyerUsdcAta,
+          treasuryAuthority,
+          bidder: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(bid));
+      const buyerUsdc = (await getAccount(provider.connection, buyerUsdcAta)).amount;
+      assert.equal(buyerUsdc.toString(), buyerUsdcFunding.toString());
+    });
+  });
+
+  // =====================================================
+  // BOUNDARY CONDITIONS
+  // =====================================================
+  describe("Boundary Conditions", () => {
+    it("should handle minimum USD amount boundary", async () => {
+      // Set very low min USD ($0.01)
+      await setLimits(
+        new anchor.BN("1000000"), // $0.01 with 8 decimals
+        new anchor.BN("1000000000000000000"),
+        new anchor.BN(60),
+        new anchor.BN(0),
+        new anchor.BN(365 * 86400)
+      );
+
+      const consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN("1000000000"), // 1 token
+          false, 0, 0, 0, 0, 0, 0,
+          new anchor.BN(1), // min 1 unit
+          new anchor.BN("1000000000"),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      // Should succeed with small amount
+      const offer = await nextOfferPda();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+
+      await program.methods
+        .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN("1000000000"),
-          0, 1, new anchor.BN(0), 0
+          0, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       assert.exists(offerAccount);
     });
 
     it("should REJECT offer below minimum USD", async () => {
       // Reset to higher minimum
-      await program.methods
-        .setLimits(
-          new anchor.BN("10000000000"), // $100 minimum (8 decimals)
-          new anchor.BN("1000000000000000000"),
-          new anchor.BN(60),
-          new anchor.BN(0),
-          new anchor.BN(365 * 86400)
-        )
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
-        .signers([owner])
-        .rpc();
+      await setLimits(
+        new anchor.BN("10000000000"), // $100 minimum (8 decimals)
+        new anchor.BN("1000000000000000000"),
+        new anchor.BN(60),
+        new anchor.BN(0),
+        new anchor.BN(365 * 86400)
+      );
 
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"), // 100k tokens
           false, 0, 0, 0, 0, 0, 0,
           new anchor.BN("1000000000"),
           new anchor.BN("100000000000000"),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1332,15 +2627,16 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       // Try to create offer worth only $10 (price is $10/token, 1 token = $10)
       await expectRejectedWith(
@@ -1348,17 +2644,20 @@ describe("OTC Comprehensive Flow Tests", () => {
           .createOfferFromConsignment(
             new anchor.BN(consignmentAccount.id.toString()),
             new anchor.BN("1000000000"), // 1 token = $10 < $100 min
-            0, 1, new anchor.BN(0), 0
+            0, usdcMint, new anchor.BN(0), 0,
+            new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+            new anchor.BN(0)
           )
           .accounts({
             desk: desk.publicKey,
-            consignment: consignment.publicKey,
+            consignment,
             tokenRegistry,
+            paymentRegistry: usdcPaymentRegistry,
             beneficiary: buyer.publicKey,
-            offer: offer.publicKey,
+            offer,
             systemProgram: SystemProgram.programId,
           })
-          .signers([buyer, offer])
+          .signers([buyer])
           .rpc(),
         "MinUsd"
       );
@@ -1376,14 +2675,17 @@ describe("OTC Comprehensive Flow Tests", () => {
         .signers([owner])
         .rpc();
 
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
           false, 500, 0, 0, 0, 0, 0,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1391,34 +2693,38 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 0
+          500, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       const buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
         provider.connection, buyer, usdcMint, buyer.publicKey
       )).address;
@@ -1434,45 +2740,53 @@ describe("OTC Comprehensive Flow Tests", () => {
 
       await expectRejectedWith(
         program.methods
-          .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+          .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
           .accounts({
             desk: desk.publicKey,
-            offer: offer.publicKey,
-            usdcMint,
+            offer,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
             deskTokenTreasury,
-            deskUsdcTreasury,
-            payerUsdcAta: thirdPartyUsdc,
-            agentUsdcAta: null,
-            deskSigner: desk.publicKey,
+            tokenRegistry,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentAta: thirdPartyUsdc,
+            agentPaymentAta: null,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
+            treasuryAuthority,
             payer: thirdParty.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([thirdParty, desk])
+          .signers([thirdParty])
           .rpc(),
         "FulfillRestricted"
       );
 
       // But beneficiary should still be able to fulfill
       await program.methods
-        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           deskTokenTreasury,
-          deskUsdcTreasury,
-          payerUsdcAta: buyerUsdcAta,
-          agentUsdcAta: null,
-          deskSigner: desk.publicKey,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
-      const offerAfter = await program.account.offer.fetch(offer.publicKey);
+      const offerAfter = await program.account.offer.fetch(offer);
       assert.isTrue(offerAfter.paid);
 
       // Disable for other tests
@@ -1512,19 +2826,207 @@ describe("OTC Comprehensive Flow Tests", () => {
     });
   });
 
+  // =====================================================
+  // PARAMETER TIMELOCK
+  // =====================================================
+  describe("Parameter Timelock", () => {
+    // Queue a limits change that raises the minimum deal to $5
+    const queueMinUsdChange = async (signer: Keypair = owner) => {
+      const { nextParamChangeId } = await program.account.desk.fetch(desk.publicKey);
+      const pendingChange = getParamChangePda(desk.publicKey, nextParamChangeId);
+      await program.methods
+        .queueParamChange({
+          setLimits: {
+            minUsdAmount8d: new anchor.BN(5 * 1e8),
+            maxTokenPerOrder: new anchor.BN(10000 * 1e9),
+            quoteExpirySecs: new anchor.BN(1800),
+            defaultUnlockDelaySecs: new anchor.BN(0),
+            maxLockupSecs: new anchor.BN(365 * 86400),
+          },
+        })
+        .accounts({ desk: desk.publicKey, owner: signer.publicKey, pendingChange })
+        .signers([signer])
+        .rpc();
+      return { changeId: nextParamChangeId, pendingChange };
+    };
+
+    beforeEach(async () => {
+      await program.methods
+        .setParamTimelock(new anchor.BN(3600))
+        .accounts({ owner: owner.publicKey, desk: desk.publicKey })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("should REJECT executing a change before the delay", async () => {
+      const { changeId, pendingChange } = await queueMinUsdChange();
+
+      const pending = await program.account.pendingParamChange.fetch(pendingChange);
+      assert.equal(pending.eta.sub(pending.queuedAt).toNumber(), 3600);
+
+      await expectRejectedWith(
+        program.methods
+          .executeParamChange(changeId)
+          .accounts({ desk: desk.publicKey, owner: owner.publicKey, pendingChange, tokenRegistry: null })
+          .signers([owner])
+          .rpc(),
+        "TimelockNotElapsed"
+      );
+
+      const deskAccount = await program.account.desk.fetch(desk.publicKey);
+      assert.equal(deskAccount.minUsdAmount8d.toNumber(), 1 * 1e8);
+    });
+
+    it("should allow the owner to cancel a queued change", async () => {
+      const { changeId, pendingChange } = await queueMinUsdChange();
+
+      await program.methods
+        .cancelParamChange(changeId)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, pendingChange })
+        .signers([owner])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(pendingChange));
+    });
+
+    it("should REJECT shortening the timelock without queueing", async () => {
+      await expectRejectedWith(
+        program.methods
+          .setParamTimelock(new anchor.BN(60))
+          .accounts({ owner: owner.publicKey, desk: desk.publicKey })
+          .signers([owner])
+          .rpc(),
+        "TimelockRequired"
+      );
+    });
+
+    it("should REJECT non-owner queueing a change", async () => {
+      await expectRejectedWith(queueMinUsdChange(buyer), "NotOwner");
+    });
+
+    it("should hold a price staleness change behind the timelock", async () => {
+      const { nextParamChangeId } = await program.account.desk.fetch(desk.publicKey);
+      const pendingChange = getParamChangePda(desk.publicKey, nextParamChangeId);
+      await program.methods
+        .queueParamChange({ setMaxPriceAge: { maxAgeSecs: new anchor.BN(86400) } })
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, pendingChange })
+        .signers([owner])
+        .rpc();
+
+      await expectRejectedWith(
+        program.methods
+          .executeParamChange(nextParamChangeId)
+          .accounts({ desk: desk.publicKey, owner: owner.publicKey, pendingChange, tokenRegistry: null })
+          .signers([owner])
+          .rpc(),
+        "TimelockNotElapsed"
+      );
+      const deskAccount = await program.account.desk.fetch(desk.publicKey);
+      assert.equal(deskAccount.maxPriceAgeSecs.toNumber(), 3600);
+    });
+  });
+
+  // =====================================================
+  // OWNER MULTISIG PROPOSALS
+  // =====================================================
+  describe("Owner Proposals", () => {
+    let proposalId: anchor.BN;
+    let proposal: PublicKey;
+
+    beforeEach(async () => {
+      await program.methods
+        .setOwners([owner.publicKey, buyer.publicKey], 2)
+        .accounts({ owner: owner.publicKey, desk: desk.publicKey })
+        .signers([owner])
+        .rpc();
+
+      proposalId = (await program.account.desk.fetch(desk.publicKey)).nextProposalId;
+      proposal = getProposalPda(desk.publicKey, proposalId);
+      await program.methods
+        .createProposal({ setAgent: { newAgent: buyer.publicKey } }, new anchor.BN(3600))
+        .accounts({ desk: desk.publicKey, proposer: owner.publicKey, proposal })
+        .signers([owner])
+        .rpc();
+    });
+
+    const closeProposal = () =>
+      program.methods
+        .closeProposal(proposalId)
+        .accounts({ desk: desk.publicKey, proposer: owner.publicKey, proposal })
+        .signers([owner])
+        .rpc();
+
+    it("should REJECT closing a live proposal", async () => {
+      await expectRejectedWith(closeProposal(), "BadState");
+    });
+
+    it("should refund the proposer when a cancelled proposal is closed", async () => {
+      await program.methods
+        .cancelProposal(proposalId)
+        .accounts({ desk: desk.publicKey, proposer: owner.publicKey, proposal })
+        .signers([owner])
+        .rpc();
+
+      // A cancelled proposal can no longer gather approvals
+      await expectRejectedWith(
+        program.methods
+          .approveProposal(proposalId)
+          .accounts({ desk: desk.publicKey, owner: buyer.publicKey, proposal })
+          .signers([buyer])
+          .rpc(),
+        "BadState"
+      );
+
+      const proposerBefore = await provider.connection.getBalance(owner.publicKey);
+      await closeProposal();
+      assert.isNull(await provider.connection.getAccountInfo(proposal));
+      assert.isTrue((await provider.connection.getBalance(owner.publicKey)) > proposerBefore);
+    });
+
+    it("should close an executed proposal", async () => {
+      await program.methods
+        .approveProposal(proposalId)
+        .accounts({ desk: desk.publicKey, owner: buyer.publicKey, proposal })
+        .signers([buyer])
+        .rpc();
+      await program.methods
+        .setAgent(buyer.publicKey)
+        .accounts({ owner: buyer.publicKey, desk: desk.publicKey, proposal })
+        .signers([buyer])
+        .rpc();
+
+      await closeProposal();
+      assert.isNull(await provider.connection.getAccountInfo(proposal));
+    });
+
+    it("should REJECT a proposal cancelled by another owner", async () => {
+      await expectRejectedWith(
+        program.methods
+          .cancelProposal(proposalId)
+          .accounts({ desk: desk.publicKey, proposer: buyer.publicKey, proposal })
+          .signers([buyer])
+          .rpc(),
+        "ConstraintHasOne"
+      );
+    });
+  });
+
   // =====================================================
   // DOUBLE-ACTION PREVENTION
   // =====================================================
   describe("Double-Action Prevention", () => {
     it("should REJECT double approval", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
           true, 500, 0, 100, 1000, 0, 365,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 500, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1532,77 +3034,86 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      await program.methods.setApprover(agent.publicKey, true)
-        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+      await program.methods.setApprover(agent.publicKey, new anchor.BN(1_000_000 * 1e8), new anchor.BN(10_000_000 * 1e8), false)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, approverAccount: getApproverPda(desk.publicKey, agent.publicKey) })
         .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 50
+          500, usdcMint, new anchor.BN(0), 50,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
 
       // First approval
       await program.methods
         .approveOffer(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          consignment: consignment.publicKey,
+          offer,
+          consignment,
           approver: agent.publicKey,
+          offerApproval: getOfferApprovalPda(offer, agent.publicKey),
         })
         .signers([agent])
         .rpc();
 
-      // Second approval should fail
+      // Second approval by the same approver fails: its approval PDA already exists
       await expectRejectedWith(
         program.methods
           .approveOffer(new anchor.BN(offerAccount.id.toString()))
           .accounts({
             desk: desk.publicKey,
-            offer: offer.publicKey,
-            consignment: consignment.publicKey,
+            offer,
+            consignment,
             approver: agent.publicKey,
+            offerApproval: getOfferApprovalPda(offer, agent.publicKey),
           })
           .signers([agent])
           .rpc(),
-        "AlreadyApproved"
+        "already in use"
       );
     });
 
     it("should REJECT double fulfillment", async () => {
-      const consignment = Keypair.generate();
+      const consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN(100_000n * 10n ** 9n),
           false, 500, 0, 0, 0, 0, 0,
           new anchor.BN(1000 * 1e9),
           new anchor.BN(50000 * 1e9),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -1610,31 +3121,35 @@ describe("OTC Comprehensive Flow Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      const offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN(10_000n * 10n ** 9n),
-          500, 1, new anchor.BN(0), 0
+          500, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       const buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
@@ -1642,48 +3157,143 @@ describe("OTC Comprehensive Flow Tests", () => {
       )).address;
       await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, 1_000_000n * 10n ** 6n);
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
 
       // First fulfillment
       await program.methods
-        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           deskTokenTreasury,
-          deskUsdcTreasury,
-          payerUsdcAta: buyerUsdcAta,
-          agentUsdcAta: null,
-          deskSigner: desk.publicKey,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
       // Second fulfillment should fail
       await expectRejectedWith(
         program.methods
-          .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+          .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
           .accounts({
             desk: desk.publicKey,
-            offer: offer.publicKey,
-            usdcMint,
+            offer,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
             deskTokenTreasury,
-            deskUsdcTreasury,
-            payerUsdcAta: buyerUsdcAta,
-            agentUsdcAta: null,
-            deskSigner: desk.publicKey,
+            tokenRegistry,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentAta: buyerUsdcAta,
+            agentPaymentAta: null,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
+            treasuryAuthority,
             payer: buyer.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
-          .signers([buyer, desk])
+          .signers([buyer])
           .rpc(),
         "BadState"
       );
     });
+
+    it("should REJECT fulfillment costing more than max payment", async () => {
+      const consignment = await nextConsignmentPda();
+      await program.methods
+        .createConsignment(
+          new anchor.BN(100_000n * 10n ** 9n),
+          false, 500, 0, 0, 0, 0, 0,
+          new anchor.BN(1000 * 1e9),
+          new anchor.BN(50000 * 1e9),
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consigner: owner.publicKey,
+          tokenMint,
+          consignerTokenAta: ownerTokenAta,
+          deskTokenTreasury,
+          tokenRegistry,
+          consignment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      const offer = await nextOfferPda();
+
+      await program.methods
+        .createOfferFromConsignment(
+          new anchor.BN(consignmentAccount.id.toString()),
+          new anchor.BN(10_000n * 10n ** 9n),
+          500, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          desk: desk.publicKey,
+          consignment,
+          tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
+          beneficiary: buyer.publicKey,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer, usdcMint, buyer.publicKey
+      )).address;
+      await mintTo(provider.connection, owner, usdcMint, buyerUsdcAta, owner, 1_000_000n * 10n ** 6n);
+
+      const offerAccount = await program.account.offer.fetch(offer);
+
+      // A one-unit cap is far below the $95,000 price
+      await expectRejectedWith(
+        program.methods
+          .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), new anchor.BN(1))
+          .accounts({
+            desk: desk.publicKey,
+            offer,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
+            deskTokenTreasury,
+            tokenRegistry,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentAta: buyerUsdcAta,
+            agentPaymentAta: null,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
+            treasuryAuthority,
+            payer: buyer.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc(),
+        "SlippageExceeded"
+      );
+
+      const after = await program.account.offer.fetch(offer);
+      assert.isFalse(after.paid);
+    });
   });
 });
@@ -52,23 +52,78 @@ describe("Emergency Refund and Critical Path Tests", () => {
     )[0];
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getPaymentRegistryPda = (desk: PublicKey, paymentMint: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("payment"), desk.toBuffer(), paymentMint.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getConsignerProceedsPda = (desk: PublicKey, consigner: PublicKey, paymentMint: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proceeds"), desk.toBuffer(), consigner.toBuffer(), paymentMint.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getConsignmentPda = (desk: PublicKey, consignmentId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("consignment"), desk.toBuffer(), consignmentId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const getOfferPda = (desk: PublicKey, offerId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("offer"), desk.toBuffer(), offerId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Consignments and offers are created at the desk's next id
+  const nextConsignmentPda = async () => {
+    const deskAccount = await program.account.desk.fetch(desk.publicKey);
+    return getConsignmentPda(desk.publicKey, deskAccount.nextConsignmentId);
+  };
+
+  const nextOfferPda = async () => {
+    const deskAccount = await program.account.desk.fetch(desk.publicKey);
+    return getOfferPda(desk.publicKey, deskAccount.nextOfferId);
+  };
+
+  // Slippage caps for fulfilment, sized to what test buyers are funded with
+  const MAX_USDC_PAYMENT = new anchor.BN(1_000_000 * 1e6);
+  const MAX_SOL_PAYMENT = new anchor.BN(5 * LAMPORTS_PER_SOL);
+
   let owner: Keypair;
   let agent: Keypair;
   let buyer: Keypair;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
   let tokenRegistry: PublicKey;
   let deskTokenTreasury: PublicKey;
   let deskUsdcTreasury: PublicKey;
+  let usdcPaymentRegistry: PublicKey;
   let ownerTokenAta: PublicKey;
   let ownerUsdcAta: PublicKey;
+  let ownerUsdcProceeds: PublicKey;
+  let ownerSolProceeds: PublicKey;
 
   beforeEach(async () => {
     owner = Keypair.generate();
     agent = Keypair.generate();
     buyer = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
 
     await Promise.all([
       airdrop(owner.publicKey, 10 * LAMPORTS_PER_SOL),
@@ -82,12 +137,12 @@ describe("Emergency Refund and Critical Path Tests", () => {
     usdcMint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
 
     // Setup desk treasuries
-    deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, desk.publicKey, true);
-    deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, desk.publicKey, true);
+    deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, treasuryAuthority, true);
+    deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, treasuryAuthority, true);
     tokenRegistry = getTokenRegistryPda(desk.publicKey, tokenMint);
 
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, desk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, desk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, treasuryAuthority, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, treasuryAuthority, true);
 
     // Initialize desk
     await program.methods
@@ -98,10 +153,25 @@ describe("Emergency Refund and Critical Path Tests", () => {
         agent: agent.publicKey,
         usdcMint,
         desk: desk.publicKey,
+        treasuryAuthority,
       })
       .signers([owner, desk])
       .rpc();
 
+    // Accept USDC as a pegged payment mint
+    usdcPaymentRegistry = getPaymentRegistryPda(desk.publicKey, usdcMint);
+    await program.methods
+      .registerPaymentMint(Array(32).fill(0), true)
+      .accounts({
+        desk: desk.publicKey,
+        authority: owner.publicKey,
+        paymentMint: usdcMint,
+        deskPaymentTreasury: deskUsdcTreasury,
+        paymentRegistry: usdcPaymentRegistry,
+      })
+      .signers([owner])
+      .rpc();
+
     // Register token
     await program.methods
       .registerToken(Array(32).fill(0), PublicKey.default, 0)
@@ -109,16 +179,27 @@ describe("Emergency Refund and Critical Path Tests", () => {
       .signers([owner])
       .rpc();
 
+    // Open the consigner's proceeds ledgers for USDC and SOL sales
+    ownerUsdcProceeds = getConsignerProceedsPda(desk.publicKey, owner.publicKey, usdcMint);
+    ownerSolProceeds = getConsignerProceedsPda(desk.publicKey, owner.publicKey, PublicKey.default);
+    for (const [paymentMint, consignerProceeds] of [[usdcMint, ownerUsdcProceeds], [PublicKey.default, ownerSolProceeds]]) {
+      await program.methods
+        .openConsignerProceeds(paymentMint)
+        .accounts({ desk: desk.publicKey, consigner: owner.publicKey, consignerProceeds, payer: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    }
+
     // Set prices
     await program.methods
       .setManualTokenPrice(new anchor.BN("1000000000")) // $10
-      .accounts({ tokenRegistry, desk: desk.publicKey, owner: owner.publicKey })
+      .accounts({ tokenRegistry, desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
     await program.methods
-      .setPrices(new anchor.BN("1000000000"), new anchor.BN("10000000000"), new anchor.BN(0), new anchor.BN(3600))
-      .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+      .setPrices(new anchor.BN("1000000000"), new anchor.BN("10000000000"), new anchor.BN(0))
+      .accounts({ desk: desk.publicKey, authority: owner.publicKey })
       .signers([owner])
       .rpc();
 
@@ -206,13 +287,14 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .accounts({
           owner: owner.publicKey,
           desk: desk.publicKey,
-          usdcMint,
-          deskSigner: desk.publicKey,
-          deskUsdcTreasury,
-          toUsdcAta: ownerUsdcAta,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          treasuryAuthority,
+          deskPaymentTreasury: deskUsdcTreasury,
+          toPaymentAta: ownerUsdcAta,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([owner, desk])
+        .signers([owner])
         .rpc();
 
       const ownerBalanceAfter = (await getAccount(provider.connection, ownerUsdcAta)).amount;
@@ -233,15 +315,16 @@ describe("Emergency Refund and Critical Path Tests", () => {
           .accounts({
             owner: buyer.publicKey,
             desk: desk.publicKey,
-            usdcMint,
-            deskSigner: desk.publicKey,
-            deskUsdcTreasury,
-            toUsdcAta: buyerUsdcAta,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
+            treasuryAuthority,
+            deskPaymentTreasury: deskUsdcTreasury,
+            toPaymentAta: buyerUsdcAta,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .signers([buyer, desk])
+          .signers([buyer])
           .rpc(),
-        "owner"
+        "MissingRole"
       );
     });
   });
@@ -250,8 +333,8 @@ describe("Emergency Refund and Critical Path Tests", () => {
   // EMERGENCY_REFUND_USDC TESTS
   // =====================================================
   describe("emergency_refund_usdc", () => {
-    let consignment: Keypair;
-    let offer: Keypair;
+    let consignment: PublicKey;
+    let offer: PublicKey;
     let buyerUsdcAta: PublicKey;
 
     beforeEach(async () => {
@@ -263,14 +346,17 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .rpc();
 
       // Create consignment
-      consignment = Keypair.generate();
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"), // 100k tokens
           false, 500, 0, 0, 0, 0, 0,
           new anchor.BN("10000000000"),
           new anchor.BN("50000000000000"),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -278,16 +364,17 @@ describe("Emergency Refund and Critical Path Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       // Create offer
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
       buyerUsdcAta = (await getOrCreateAssociatedTokenAccount(
         provider.connection, buyer, usdcMint, buyer.publicKey
       )).address;
@@ -297,37 +384,44 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN("10000000000"), // 10 tokens
-          500, 1, new anchor.BN(0), 0
+          500, usdcMint, new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
+          paymentRegistry: usdcPaymentRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Fulfill offer
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       await program.methods
-        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferUsdc(new anchor.BN(offerAccount.id.toString()), MAX_USDC_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          offer,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           deskTokenTreasury,
-          deskUsdcTreasury,
-          payerUsdcAta: buyerUsdcAta,
-          agentUsdcAta: null,
-          deskSigner: desk.publicKey,
+          tokenRegistry,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentAta: buyerUsdcAta,
+          agentPaymentAta: null,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
     });
 
@@ -339,22 +433,26 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .signers([owner])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
 
       await expectRejectedWith(
         program.methods
           .emergencyRefundUsdc(new anchor.BN(offerAccount.id.toString()))
           .accounts({
             desk: desk.publicKey,
-            deskSigner: desk.publicKey,
-            offer: offer.publicKey,
-            usdcMint,
+            treasuryAuthority,
+            offer,
+            tokenRegistry,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
             caller: buyer.publicKey,
-            deskUsdcTreasury,
-            payerUsdcRefund: buyerUsdcAta,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentRefund: buyerUsdcAta,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .signers([buyer, desk])
+          .signers([buyer])
           .rpc(),
         "BadState"
       );
@@ -368,22 +466,26 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .signers([owner])
         .rpc();
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
 
       await expectRejectedWith(
         program.methods
           .emergencyRefundUsdc(new anchor.BN(offerAccount.id.toString()))
           .accounts({
             desk: desk.publicKey,
-            deskSigner: desk.publicKey,
-            offer: offer.publicKey,
-            usdcMint,
+            treasuryAuthority,
+            offer,
+            tokenRegistry,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
             caller: buyer.publicKey,
-            deskUsdcTreasury,
-            payerUsdcRefund: buyerUsdcAta,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentRefund: buyerUsdcAta,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
-          .signers([buyer, desk])
+          .signers([buyer])
           .rpc(),
         "TooEarlyForRefund"
       );
@@ -393,22 +495,26 @@ describe("Emergency Refund and Critical Path Tests", () => {
       // Wait for deadline (1 second)
       await new Promise(r => setTimeout(r, 2000));
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       const buyerUsdcBefore = (await getAccount(provider.connection, buyerUsdcAta)).amount;
 
       await program.methods
         .emergencyRefundUsdc(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          deskSigner: desk.publicKey,
-          offer: offer.publicKey,
-          usdcMint,
+          treasuryAuthority,
+          offer,
+          tokenRegistry,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
           caller: buyer.publicKey,
-          deskUsdcTreasury,
-          payerUsdcRefund: buyerUsdcAta,
+          deskPaymentTreasury: deskUsdcTreasury,
+          payerPaymentRefund: buyerUsdcAta,
+          consignment,
+          consignerProceeds: ownerUsdcProceeds,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
       // Verify refund
@@ -416,17 +522,72 @@ describe("Emergency Refund and Critical Path Tests", () => {
       assert.isTrue(BigInt(buyerUsdcAfter) > BigInt(buyerUsdcBefore));
 
       // Verify offer is cancelled
-      const offerAfter = await program.account.offer.fetch(offer.publicKey);
+      const offerAfter = await program.account.offer.fetch(offer);
       assert.isTrue(offerAfter.cancelled);
     });
+
+    it("should REJECT emergency refund once the consigner has withdrawn the proceeds", async () => {
+      await program.methods
+        .withdrawProceedsUsdc()
+        .accounts({
+          desk: desk.publicKey,
+          treasuryAuthority,
+          consigner: owner.publicKey,
+          paymentMint: usdcMint,
+          paymentRegistry: usdcPaymentRegistry,
+          consignerProceeds: ownerUsdcProceeds,
+          deskPaymentTreasury: deskUsdcTreasury,
+          consignerPaymentAta: ownerUsdcAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+
+      await new Promise(r => setTimeout(r, 2000));
+
+      const offerAccount = await program.account.offer.fetch(offer);
+      const consignmentBefore = await program.account.consignment.fetch(consignment);
+      const buyerUsdcBefore = (await getAccount(provider.connection, buyerUsdcAta)).amount;
+      const ownerUsdcBefore = (await getAccount(provider.connection, ownerUsdcAta)).amount;
+
+      await expectRejectedWith(
+        program.methods
+          .emergencyRefundUsdc(new anchor.BN(offerAccount.id.toString()))
+          .accounts({
+            desk: desk.publicKey,
+            treasuryAuthority,
+            offer,
+            tokenRegistry,
+            paymentMint: usdcMint,
+            paymentRegistry: usdcPaymentRegistry,
+            caller: buyer.publicKey,
+            deskPaymentTreasury: deskUsdcTreasury,
+            payerPaymentRefund: buyerUsdcAta,
+            consignment,
+            consignerProceeds: ownerUsdcProceeds,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([buyer])
+          .rpc(),
+        "BadState"
+      );
+
+      // The sale stands: tokens stay sold and both payment balances are untouched
+      const consignmentAfter = await program.account.consignment.fetch(consignment);
+      assert.equal(consignmentAfter.remainingAmount.toString(), consignmentBefore.remainingAmount.toString());
+      assert.equal((await getAccount(provider.connection, buyerUsdcAta)).amount, buyerUsdcBefore);
+      assert.equal((await getAccount(provider.connection, ownerUsdcAta)).amount, ownerUsdcBefore);
+      const offerAfter = await program.account.offer.fetch(offer);
+      assert.isFalse(offerAfter.cancelled);
+    });
   });
 
   // =====================================================
   // EMERGENCY_REFUND_SOL TESTS
   // =====================================================
   describe("emergency_refund_sol", () => {
-    let consignment: Keypair;
-    let offer: Keypair;
+    let consignment: PublicKey;
+    let offer: PublicKey;
 
     beforeEach(async () => {
       // Enable emergency refund
@@ -437,14 +598,17 @@ describe("Emergency Refund and Critical Path Tests", () => {
         .rpc();
 
       // Create consignment
-      consignment = Keypair.generate();
+      consignment = await nextConsignmentPda();
       await program.methods
         .createConsignment(
           new anchor.BN("100000000000000"),
           false, 500, 0, 0, 0, 0, 0,
           new anchor.BN("1000000000"), // 1 token min (small amount)
           new anchor.BN("50000000000000"),
-          true, false, 0, new anchor.BN(3600)
+          true, false, 500, new anchor.BN(3600),
+          0, 0, 0,
+          new anchor.BN(0),
+          false, new anchor.BN(0), false
         )
         .accounts({
           desk: desk.publicKey,
@@ -452,76 +616,84 @@ describe("Emergency Refund and Critical Path Tests", () => {
           tokenMint,
           consignerTokenAta: ownerTokenAta,
           deskTokenTreasury,
-          consignment: consignment.publicKey,
+          tokenRegistry,
+          consignment,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
-        .signers([owner, consignment])
+        .signers([owner])
         .rpc();
 
       // Create SOL offer
-      const consignmentAccount = await program.account.consignment.fetch(consignment.publicKey);
-      offer = Keypair.generate();
+      const consignmentAccount = await program.account.consignment.fetch(consignment);
+      offer = await nextOfferPda();
 
       await program.methods
         .createOfferFromConsignment(
           new anchor.BN(consignmentAccount.id.toString()),
           new anchor.BN("1000000000"), // 1 token = $10 -> 0.1 SOL
-          500, 0, // SOL payment
-          new anchor.BN(0), 0
+          500, PublicKey.default, // SOL payment
+          new anchor.BN(0), 0,
+          new anchor.BN(0), new anchor.BN(0), new anchor.BN(0),
+          new anchor.BN(0)
         )
         .accounts({
           desk: desk.publicKey,
-          consignment: consignment.publicKey,
+          consignment,
           tokenRegistry,
           beneficiary: buyer.publicKey,
-          offer: offer.publicKey,
+          offer,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, offer])
+        .signers([buyer])
         .rpc();
 
       // Fulfill with SOL
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       await program.methods
-        .fulfillOfferSol(new anchor.BN(offerAccount.id.toString()))
+        .fulfillOfferSol(new anchor.BN(offerAccount.id.toString()), MAX_SOL_PAYMENT)
         .accounts({
           desk: desk.publicKey,
-          offer: offer.publicKey,
+          offer,
           deskTokenTreasury,
+          tokenRegistry,
           agent: null,
-          deskSigner: desk.publicKey,
+          consignment,
+          consignerProceeds: ownerSolProceeds,
+          treasuryAuthority,
           payer: buyer.publicKey,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
     });
 
     it("should execute SOL emergency refund after deadline", async () => {
       await new Promise(r => setTimeout(r, 2000));
 
-      const offerAccount = await program.account.offer.fetch(offer.publicKey);
+      const offerAccount = await program.account.offer.fetch(offer);
       const buyerSolBefore = await provider.connection.getBalance(buyer.publicKey);
 
       await program.methods
         .emergencyRefundSol(new anchor.BN(offerAccount.id.toString()))
         .accounts({
           desk: desk.publicKey,
-          deskSigner: desk.publicKey,
-          offer: offer.publicKey,
+          offer,
+          tokenRegistry,
           caller: buyer.publicKey,
           payerRefund: buyer.publicKey,
+          consignment,
+          consignerProceeds: ownerSolProceeds,
           systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, desk])
+        .signers([buyer])
         .rpc();
 
       const buyerSolAfter = await provider.connection.getBalance(buyer.publicKey);
       // Balance should increase (minus tx fee)
       assert.isTrue(buyerSolAfter > buyerSolBefore - 10000);
 
-      const offerAfter = await program.account.offer.fetch(offer.publicKey);
+      const offerAfter = await program.account.offer.fetch(offer);
       assert.isTrue(offerAfter.cancelled);
     });
   });
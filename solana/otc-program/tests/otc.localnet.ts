@@ -26,6 +26,13 @@ describe("otc localnet smoke", () => {
     )[0];
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
   it("initializes desk and deposits tokens", async () => {
     const owner = Keypair.generate();
     const sig = await provider.connection.requestAirdrop(owner.publicKey, 2e9);
@@ -33,20 +40,21 @@ describe("otc localnet smoke", () => {
     const agent = Keypair.generate();
     await airdrop(agent.publicKey, 2e9);
     const desk = Keypair.generate();
+    const treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
 
     const tokenMint = await createMint(provider.connection, owner, owner.publicKey, null, 9);
     const usdcMint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
 
-    const deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, desk.publicKey, true);
-    const deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, desk.publicKey, true);
+    const deskTokenTreasury = getAssociatedTokenAddressSync(tokenMint, treasuryAuthority, true);
+    const deskUsdcTreasury = getAssociatedTokenAddressSync(usdcMint, treasuryAuthority, true);
     const tokenRegistry = getTokenRegistryPda(desk.publicKey, tokenMint);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, desk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, desk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, treasuryAuthority, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, treasuryAuthority, true);
 
     // Initialize desk without tokenMint (multi-token architecture)
     await program.methods
       .initDesk(new BN(500000000), new BN(1800))
-      .accounts({ desk: desk.publicKey, usdcMint, owner: owner.publicKey, agent: agent.publicKey, payer: owner.publicKey })
+      .accounts({ desk: desk.publicKey, treasuryAuthority, usdcMint, owner: owner.publicKey, agent: agent.publicKey, payer: owner.publicKey })
       .signers([owner, desk])
       .rpc();
 
@@ -34,6 +34,7 @@ describe("Pool Oracle Security Tests", () => {
   // Test keypairs
   let owner: Keypair;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
   let tokenRegistry: PublicKey;
@@ -50,9 +51,58 @@ describe("Pool Oracle Security Tests", () => {
     await provider.connection.confirmTransaction(sig, "confirmed");
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getParamChangePda = (desk: PublicKey, changeId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("param_change"), desk.toBuffer(), changeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Economic and oracle parameters go through the desk timelock (zero delay on a fresh desk)
+  const applyParamChange = async (
+    deskKey: PublicKey,
+    signer: Keypair,
+    change: anchor.IdlTypes<Otc>["paramChange"],
+    registry: PublicKey | null = null
+  ) => {
+    const { nextParamChangeId } = await program.account.desk.fetch(deskKey);
+    const pendingChange = getParamChangePda(deskKey, nextParamChangeId);
+    await program.methods
+      .queueParamChange(change)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange })
+      .signers([signer])
+      .rpc();
+    await program.methods
+      .executeParamChange(nextParamChangeId)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange, tokenRegistry: registry })
+      .signers([signer])
+      .rpc();
+  };
+
+  const configurePoolOracle = (
+    minLiquidity: anchor.BN,
+    maxTwapDeviationBps: number,
+    minUpdateIntervalSecs: anchor.BN,
+    { registry = tokenRegistry, deskKey = desk.publicKey, signer = owner } = {}
+  ) =>
+    applyParamChange(
+      deskKey,
+      signer,
+      { configurePoolOracle: { tokenRegistry: registry, minLiquidity, maxTwapDeviationBps, minUpdateIntervalSecs } },
+      registry
+    );
+
   before(async () => {
     owner = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
     mockPool = Keypair.generate();
 
     await airdrop(owner.publicKey, 10 * LAMPORTS_PER_SOL);
@@ -78,12 +128,12 @@ describe("Pool Oracle Security Tests", () => {
     // Setup desk treasuries
     deskTokenTreasury = getAssociatedTokenAddressSync(
       tokenMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
     deskUsdcTreasury = getAssociatedTokenAddressSync(
       usdcMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
 
@@ -91,14 +141,14 @@ describe("Pool Oracle Security Tests", () => {
       provider.connection,
       owner,
       tokenMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
     await getOrCreateAssociatedTokenAccount(
       provider.connection,
       owner,
       usdcMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
 
@@ -111,6 +161,7 @@ describe("Pool Oracle Security Tests", () => {
         agent: owner.publicKey,
         usdcMint,
         desk: desk.publicKey,
+        treasuryAuthority,
       })
       .signers([owner, desk])
       .rpc();
@@ -183,19 +234,11 @@ describe("Pool Oracle Security Tests", () => {
       const maxTwapDeviationBps = 500; // 5%
       const minUpdateIntervalSecs = new anchor.BN(60); // 1 minute
 
-      await program.methods
-        .configurePoolOracle(
-          minLiquidity,
-          maxTwapDeviationBps,
-          minUpdateIntervalSecs
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        minLiquidity,
+        maxTwapDeviationBps,
+        minUpdateIntervalSecs
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), minLiquidity.toString());
@@ -207,37 +250,21 @@ describe("Pool Oracle Security Tests", () => {
     });
 
     it("should reject min update interval less than 30 seconds", async () => {
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(10) // Too short
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(10) // Too short
+      );
 
       await expectRejectedWith(promise, "AmountRange");
     });
 
     it("should reject max TWAP deviation over 50%", async () => {
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          6000, // 60% - too high
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        6000, // 60% - too high
+        new anchor.BN(60)
+      );
 
       await expectRejectedWith(promise, "AmountRange");
     });
@@ -246,19 +273,12 @@ describe("Pool Oracle Security Tests", () => {
       const nonOwner = Keypair.generate();
       await airdrop(nonOwner.publicKey, 1 * LAMPORTS_PER_SOL);
 
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          500,
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: nonOwner.publicKey,
-        })
-        .signers([nonOwner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        500,
+        new anchor.BN(60),
+        { signer: nonOwner }
+      );
 
       try {
         await promise;
@@ -292,19 +312,11 @@ describe("Pool Oracle Security Tests", () => {
   describe("Minimum Liquidity Check", () => {
     it("should allow price update when liquidity is sufficient", async () => {
       // First, disable TWAP and set low min liquidity for this test
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(1_000 * 1e6), // $1,000 min (we have $100,000)
-          0, // Disable TWAP
-          new anchor.BN(30) // 30 second interval
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(1_000 * 1e6), // $1,000 min (we have $100,000)
+        0, // Disable TWAP
+        new anchor.BN(30) // 30 second interval
+      );
 
       // Wait for min interval
       await new Promise((r) => setTimeout(r, 1000));
@@ -330,19 +342,11 @@ describe("Pool Oracle Security Tests", () => {
       // In practice, this would require simulating price manipulation
       
       // Configure strict EMA deviation settings
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0), // No min liquidity
-          100, // 1% max deviation from EMA
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0), // No min liquidity
+        100, // 1% max deviation from EMA
+        new anchor.BN(30)
+      );
 
       // The EMA protection works as follows:
       // 1. First update establishes baseline price (spot price used directly)
@@ -365,25 +369,17 @@ describe("Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
 
       // Configure with 60 second interval
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(60) // 60 second minimum interval
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(60) // 60 second minimum interval
+      );
 
       // The rate limiting prevents spam and makes manipulation more expensive
       // Attacker would need to maintain manipulated price for full interval
@@ -36,6 +36,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
   let owner: Keypair;
   let attacker: Keypair;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
   let tokenRegistry: PublicKey;
@@ -52,12 +53,61 @@ describe("Rigorous Pool Oracle Security Tests", () => {
     await provider.connection.confirmTransaction(sig, "confirmed");
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getParamChangePda = (desk: PublicKey, changeId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("param_change"), desk.toBuffer(), changeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Economic and oracle parameters go through the desk timelock (zero delay on a fresh desk)
+  const applyParamChange = async (
+    deskKey: PublicKey,
+    signer: Keypair,
+    change: anchor.IdlTypes<Otc>["paramChange"],
+    registry: PublicKey | null = null
+  ) => {
+    const { nextParamChangeId } = await program.account.desk.fetch(deskKey);
+    const pendingChange = getParamChangePda(deskKey, nextParamChangeId);
+    await program.methods
+      .queueParamChange(change)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange })
+      .signers([signer])
+      .rpc();
+    await program.methods
+      .executeParamChange(nextParamChangeId)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange, tokenRegistry: registry })
+      .signers([signer])
+      .rpc();
+  };
+
+  const configurePoolOracle = (
+    minLiquidity: anchor.BN,
+    maxTwapDeviationBps: number,
+    minUpdateIntervalSecs: anchor.BN,
+    { registry = tokenRegistry, deskKey = desk.publicKey, signer = owner } = {}
+  ) =>
+    applyParamChange(
+      deskKey,
+      signer,
+      { configurePoolOracle: { tokenRegistry: registry, minLiquidity, maxTwapDeviationBps, minUpdateIntervalSecs } },
+      registry
+    );
+
   const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
 
   before(async () => {
     owner = Keypair.generate();
     attacker = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
     mockPool = Keypair.generate();
 
     await airdrop(owner.publicKey, 20 * LAMPORTS_PER_SOL);
@@ -84,12 +134,12 @@ describe("Rigorous Pool Oracle Security Tests", () => {
     // Setup desk treasuries
     deskTokenTreasury = getAssociatedTokenAddressSync(
       tokenMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
     deskUsdcTreasury = getAssociatedTokenAddressSync(
       usdcMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
 
@@ -97,14 +147,14 @@ describe("Rigorous Pool Oracle Security Tests", () => {
       provider.connection,
       owner,
       tokenMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
     await getOrCreateAssociatedTokenAccount(
       provider.connection,
       owner,
       usdcMint,
-      desk.publicKey,
+      treasuryAuthority,
       true
     );
 
@@ -117,6 +167,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         agent: owner.publicKey,
         usdcMint,
         desk: desk.publicKey,
+        treasuryAuthority,
       })
       .signers([owner, desk])
       .rpc();
@@ -201,7 +252,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -217,7 +268,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -231,7 +282,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -245,7 +296,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -261,7 +312,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -279,83 +330,51 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
     });
 
     it("should accept zero minimum liquidity (disabled)", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0), // No minimum
-          0,
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0), // No minimum
+        0,
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), "0");
     });
 
     it("should correctly set minimum liquidity $1", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(1 * 1e6), // $1 minimum
-          0,
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(1 * 1e6), // $1 minimum
+        0,
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), (1e6).toString());
     });
 
     it("should correctly set minimum liquidity $100,000", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(100_000 * 1e6), // $100,000 minimum
-          0,
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(100_000 * 1e6), // $100,000 minimum
+        0,
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), (100_000 * 1e6).toString());
     });
 
     it("should correctly set minimum liquidity $1M", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(1_000_000 * 1e6), // $1M minimum
-          0,
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(1_000_000 * 1e6), // $1M minimum
+        0,
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), (1_000_000 * 1e6).toString());
@@ -364,75 +383,43 @@ describe("Rigorous Pool Oracle Security Tests", () => {
 
   describe("Rate Limiting Enforcement", () => {
     it("should enforce minimum 30 second interval", async () => {
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(29) // 29 seconds - should fail
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(29) // 29 seconds - should fail
+      );
 
       await expectRejectedWith(promise, "AmountRange");
     });
 
     it("should accept exactly 30 second interval", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(30) // Exactly 30 seconds
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(30) // Exactly 30 seconds
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minUpdateIntervalSecs.toString(), "30");
     });
 
     it("should accept large intervals (1 hour)", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(3600) // 1 hour
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(3600) // 1 hour
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minUpdateIntervalSecs.toString(), "3600");
     });
 
     it("should accept very large intervals (24 hours)", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          new anchor.BN(86400) // 24 hours
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        new anchor.BN(86400) // 24 hours
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minUpdateIntervalSecs.toString(), "86400");
@@ -441,94 +428,54 @@ describe("Rigorous Pool Oracle Security Tests", () => {
 
   describe("TWAP Deviation Bounds", () => {
     it("should reject TWAP deviation > 50%", async () => {
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          5001, // 50.01% - should fail
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        5001, // 50.01% - should fail
+        new anchor.BN(30)
+      );
 
       await expectRejectedWith(promise, "AmountRange");
     });
 
     it("should accept exactly 50% deviation", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          5000, // Exactly 50%
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        5000, // Exactly 50%
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.maxTwapDeviationBps, 5000);
     });
 
     it("should accept 0% deviation (disabled)", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0, // Disabled
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0, // Disabled
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.maxTwapDeviationBps, 0);
     });
 
     it("should accept 1% deviation", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          100, // 1%
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        100, // 1%
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.maxTwapDeviationBps, 100);
     });
 
     it("should accept 5% deviation", async () => {
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          500, // 5%
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        500, // 5%
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.maxTwapDeviationBps, 500);
@@ -537,19 +484,12 @@ describe("Rigorous Pool Oracle Security Tests", () => {
 
   describe("Access Control", () => {
     it("should reject non-owner from configuring oracle", async () => {
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          500,
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: attacker.publicKey,
-        })
-        .signers([attacker])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        500,
+        new anchor.BN(60),
+        { signer: attacker }
+      );
 
       try {
         await promise;
@@ -569,7 +509,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: attacker.publicKey,
+          authority: attacker.publicKey,
         })
         .signers([attacker])
         .rpc();
@@ -580,7 +520,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
       } catch (error) {
         const errStr = String(error).toLowerCase();
         assert.isTrue(
-          errStr.includes("constraint") || errStr.includes("owner"),
+          errStr.includes("missingrole"),
           `Unexpected error: ${String(error)}`
         );
       }
@@ -599,24 +539,18 @@ describe("Rigorous Pool Oracle Security Tests", () => {
           agent: owner.publicKey,
           usdcMint,
           desk: otherDesk.publicKey,
+          treasuryAuthority: getTreasuryAuthorityPda(otherDesk.publicKey),
         })
         .signers([owner, otherDesk])
         .rpc();
 
       // Try to configure registry from original desk with other desk
-      const promise = program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          500,
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry, // From original desk
-          desk: otherDesk.publicKey, // Different desk
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = configurePoolOracle(
+        new anchor.BN(0),
+        500,
+        new anchor.BN(60),
+        { deskKey: otherDesk.publicKey }
+      );
 
       await expectRejectedWith(promise, "BadState");
     });
@@ -628,15 +562,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
       const maxTwapDev = 250;
       const minInterval = new anchor.BN(180);
 
-      await program.methods
-        .configurePoolOracle(minLiquidity, maxTwapDev, minInterval)
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(minLiquidity, maxTwapDev, minInterval);
 
       // Fetch and verify
       let registry = await program.account.tokenRegistry.fetch(tokenRegistry);
@@ -650,7 +576,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -664,37 +590,21 @@ describe("Rigorous Pool Oracle Security Tests", () => {
 
     it("should allow reconfiguration", async () => {
       // First config
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(10_000 * 1e6),
-          100,
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(10_000 * 1e6),
+        100,
+        new anchor.BN(60)
+      );
 
       let registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), (10_000 * 1e6).toString());
 
       // Second config
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(20_000 * 1e6),
-          200,
-          new anchor.BN(120)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(20_000 * 1e6),
+        200,
+        new anchor.BN(120)
+      );
 
       registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), (20_000 * 1e6).toString());
@@ -707,19 +617,11 @@ describe("Rigorous Pool Oracle Security Tests", () => {
     it("should handle max u64 for min_liquidity", async () => {
       const maxU64 = new anchor.BN("18446744073709551615");
       
-      await program.methods
-        .configurePoolOracle(
-          maxU64,
-          0,
-          new anchor.BN(30)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        maxU64,
+        0,
+        new anchor.BN(30)
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minLiquidity.toString(), maxU64.toString());
@@ -728,19 +630,11 @@ describe("Rigorous Pool Oracle Security Tests", () => {
     it("should handle max allowed interval", async () => {
       const maxSafeInterval = new anchor.BN(2147483647); // i64 max for practical purposes
       
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(0),
-          0,
-          maxSafeInterval
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(0),
+        0,
+        maxSafeInterval
+      );
 
       const registry = await program.account.tokenRegistry.fetch(tokenRegistry);
       assert.equal(registry.minUpdateIntervalSecs.toString(), maxSafeInterval.toString());
@@ -753,19 +647,11 @@ describe("Rigorous Pool Oracle Security Tests", () => {
       const decimalsBefore = registryBefore.decimals;
 
       // Configure
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(5000 * 1e6),
-          300,
-          new anchor.BN(90)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(5000 * 1e6),
+        300,
+        new anchor.BN(90)
+      );
 
       const registryAfter = await program.account.tokenRegistry.fetch(tokenRegistry);
       
@@ -783,19 +669,11 @@ describe("Rigorous Pool Oracle Security Tests", () => {
       const ownerBefore = deskBefore.owner.toBase58();
       const agentBefore = deskBefore.agent.toBase58();
       
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(25_000 * 1e6),
-          400,
-          new anchor.BN(45)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(25_000 * 1e6),
+        400,
+        new anchor.BN(45)
+      );
 
       const deskAfter = await program.account.desk.fetch(desk.publicKey);
       
@@ -843,34 +721,19 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .rpc();
 
       // Configure first token
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(10_000 * 1e6),
-          100,
-          new anchor.BN(60)
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(10_000 * 1e6),
+        100,
+        new anchor.BN(60)
+      );
 
       // Configure second token differently
-      await program.methods
-        .configurePoolOracle(
-          new anchor.BN(50_000 * 1e6),
-          500,
-          new anchor.BN(300)
-        )
-        .accounts({
-          tokenRegistry: tokenRegistry2,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        new anchor.BN(50_000 * 1e6),
+        500,
+        new anchor.BN(300),
+        { registry: tokenRegistry2 }
+      );
 
       // Verify independent configs
       const reg1 = await program.account.tokenRegistry.fetch(tokenRegistry);
@@ -895,19 +758,11 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         minUpdateIntervalSecs: new anchor.BN(300) // 5 minutes
       };
 
-      await program.methods
-        .configurePoolOracle(
-          prodConfig.minLiquidity,
-          prodConfig.maxTwapDeviationBps,
-          prodConfig.minUpdateIntervalSecs
-        )
-        .accounts({
-          tokenRegistry,
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await configurePoolOracle(
+        prodConfig.minLiquidity,
+        prodConfig.maxTwapDeviationBps,
+        prodConfig.minUpdateIntervalSecs
+      );
 
       // Set reasonable initial price
       await program.methods
@@ -915,7 +770,7 @@ describe("Rigorous Pool Oracle Security Tests", () => {
         .accounts({
           tokenRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
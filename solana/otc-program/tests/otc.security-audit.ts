@@ -47,10 +47,72 @@ describe("OTC Security Audit Tests", () => {
     )[0];
   };
 
+  const getTreasuryAuthorityPda = (desk: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), desk.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getApproverPda = (desk: PublicKey, approver: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("approver"), desk.toBuffer(), approver.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getRoleGrantPda = (desk: PublicKey, roleIndex: number, holder: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), desk.toBuffer(), Buffer.from([roleIndex]), holder.toBuffer()],
+      program.programId
+    )[0];
+  };
+
+  const getParamChangePda = (desk: PublicKey, changeId: anchor.BN) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("param_change"), desk.toBuffer(), changeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // Economic and oracle parameters go through the desk timelock (zero delay on a fresh desk)
+  const applyParamChange = async (
+    deskKey: PublicKey,
+    signer: Keypair,
+    change: anchor.IdlTypes<Otc>["paramChange"],
+    registry: PublicKey | null = null
+  ) => {
+    const { nextParamChangeId } = await program.account.desk.fetch(deskKey);
+    const pendingChange = getParamChangePda(deskKey, nextParamChangeId);
+    await program.methods
+      .queueParamChange(change)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange })
+      .signers([signer])
+      .rpc();
+    await program.methods
+      .executeParamChange(nextParamChangeId)
+      .accounts({ desk: deskKey, owner: signer.publicKey, pendingChange, tokenRegistry: registry })
+      .signers([signer])
+      .rpc();
+  };
+
+  const setLimits = (
+    minUsdAmount8d: anchor.BN,
+    maxTokenPerOrder: anchor.BN,
+    quoteExpirySecs: anchor.BN,
+    defaultUnlockDelaySecs: anchor.BN,
+    maxLockupSecs: anchor.BN,
+    { signer = owner } = {}
+  ) =>
+    applyParamChange(desk.publicKey, signer, {
+      setLimits: { minUsdAmount8d, maxTokenPerOrder, quoteExpirySecs, defaultUnlockDelaySecs, maxLockupSecs },
+    });
+
   // Test keypairs
   let owner: Keypair;
   let attacker: Keypair;
   let desk: Keypair;
+  let treasuryAuthority: PublicKey;
   let attackerDesk: Keypair;
   let tokenMint: PublicKey;
   let usdcMint: PublicKey;
@@ -61,6 +123,7 @@ describe("OTC Security Audit Tests", () => {
     owner = Keypair.generate();
     attacker = Keypair.generate();
     desk = Keypair.generate();
+    treasuryAuthority = getTreasuryAuthorityPda(desk.publicKey);
     attackerDesk = Keypair.generate();
 
     await Promise.all([
@@ -74,8 +137,8 @@ describe("OTC Security Audit Tests", () => {
     usdcMint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
 
     // Setup desk treasuries
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, desk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, desk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, tokenMint, treasuryAuthority, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, owner, usdcMint, treasuryAuthority, true);
 
     // Initialize owner desk
     await program.methods
@@ -86,6 +149,7 @@ describe("OTC Security Audit Tests", () => {
         agent: owner.publicKey,
         usdcMint,
         desk: desk.publicKey,
+        treasuryAuthority,
       })
       .signers([owner, desk])
       .rpc();
@@ -109,7 +173,7 @@ describe("OTC Security Audit Tests", () => {
       .accounts({
         tokenRegistry: ownerRegistry,
         desk: desk.publicKey,
-        owner: owner.publicKey,
+        authority: owner.publicKey,
       })
       .signers([owner])
       .rpc();
@@ -118,29 +182,29 @@ describe("OTC Security Audit Tests", () => {
       .setPrices(
         new anchor.BN(10 * 1e8),
         new anchor.BN(150 * 1e8),
-        new anchor.BN(0),
-        new anchor.BN(3600)
+        new anchor.BN(0)
       )
       .accounts({ 
         desk: desk.publicKey,
-        owner: owner.publicKey,
+        authority: owner.publicKey,
       })
       .signers([owner])
       .rpc();
 
     // Add owner as approver
     await program.methods
-      .setApprover(owner.publicKey, true)
+      .setApprover(owner.publicKey, new anchor.BN(1_000_000 * 1e8), new anchor.BN(10_000_000 * 1e8), false)
       .accounts({ 
         desk: desk.publicKey,
         owner: owner.publicKey,
+        approverAccount: getApproverPda(desk.publicKey, owner.publicKey),
       })
       .signers([owner])
       .rpc();
 
     // Setup attacker desk
-    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, tokenMint, attackerDesk.publicKey, true);
-    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, usdcMint, attackerDesk.publicKey, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, tokenMint, getTreasuryAuthorityPda(attackerDesk.publicKey), true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, attacker, usdcMint, getTreasuryAuthorityPda(attackerDesk.publicKey), true);
 
     await program.methods
       .initDesk(new anchor.BN(1 * 1e8), new anchor.BN(86400))
@@ -150,16 +214,18 @@ describe("OTC Security Audit Tests", () => {
         agent: attacker.publicKey,
         usdcMint,
         desk: attackerDesk.publicKey,
+        treasuryAuthority: getTreasuryAuthorityPda(attackerDesk.publicKey),
       })
       .signers([attacker, attackerDesk])
       .rpc();
 
     // Attacker adds themselves as approver
     await program.methods
-      .setApprover(attacker.publicKey, true)
+      .setApprover(attacker.publicKey, new anchor.BN(1_000_000 * 1e8), new anchor.BN(10_000_000 * 1e8), false)
       .accounts({ 
         desk: attackerDesk.publicKey,
         owner: attacker.publicKey,
+        approverAccount: getApproverPda(attackerDesk.publicKey, attacker.publicKey),
       })
       .signers([attacker])
       .rpc();
@@ -183,7 +249,7 @@ describe("OTC Security Audit Tests", () => {
       .accounts({
         tokenRegistry: attackerRegistry,
         desk: attackerDesk.publicKey,
-        owner: attacker.publicKey,
+        authority: attacker.publicKey,
       })
       .signers([attacker])
       .rpc();
@@ -196,7 +262,7 @@ describe("OTC Security Audit Tests", () => {
         .accounts({
           tokenRegistry: ownerRegistry,
           desk: attackerDesk.publicKey,
-          owner: attacker.publicKey,
+          authority: attacker.publicKey,
         })
         .signers([attacker])
         .rpc();
@@ -212,7 +278,7 @@ describe("OTC Security Audit Tests", () => {
         .accounts({
           tokenRegistry: ownerRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -225,39 +291,25 @@ describe("OTC Security Audit Tests", () => {
 
   describe("HIGH: Minimum Quote Expiry", () => {
     it("should REJECT quote expiry less than 60 seconds", async () => {
-      const promise = program.methods
-        .setLimits(
-          new anchor.BN(5 * 1e8),
-          new anchor.BN(10000 * 1e9),
-          new anchor.BN(30),
-          new anchor.BN(0),
-          new anchor.BN(365 * 86400)
-        )
-        .accounts({ 
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      const promise = setLimits(
+        new anchor.BN(5 * 1e8),
+        new anchor.BN(10000 * 1e9),
+        new anchor.BN(30),
+        new anchor.BN(0),
+        new anchor.BN(365 * 86400)
+      );
 
       await expectRejectedWith(promise, "AmountRange");
     });
 
     it("should ALLOW quote expiry >= 60 seconds", async () => {
-      await program.methods
-        .setLimits(
-          new anchor.BN(5 * 1e8),
-          new anchor.BN(10000 * 1e9),
-          new anchor.BN(60),
-          new anchor.BN(0),
-          new anchor.BN(365 * 86400)
-        )
-        .accounts({ 
-          desk: desk.publicKey,
-          owner: owner.publicKey,
-        })
-        .signers([owner])
-        .rpc();
+      await setLimits(
+        new anchor.BN(5 * 1e8),
+        new anchor.BN(10000 * 1e9),
+        new anchor.BN(60),
+        new anchor.BN(0),
+        new anchor.BN(365 * 86400)
+      );
 
       const deskAccount = await program.account.desk.fetch(desk.publicKey);
       assert.equal(deskAccount.quoteExpirySecs.toNumber(), 60);
@@ -271,38 +323,31 @@ describe("OTC Security Audit Tests", () => {
           .setPrices(
             new anchor.BN(1 * 1e8),
             new anchor.BN(100 * 1e8),
-            new anchor.BN(0),
-            new anchor.BN(3600)
+            new anchor.BN(0)
           )
           .accounts({ 
             desk: desk.publicKey,
-            owner: attacker.publicKey,
+            authority: attacker.publicKey,
           })
           .signers([attacker])
           .rpc();
         assert.fail("Expected to fail");
       } catch (error) {
         const msg = String(error).toLowerCase();
-        assert.isTrue(msg.includes("constraint") || msg.includes("owner"));
+        assert.isTrue(msg.includes("missingrole"));
       }
     });
 
     it("should REJECT non-owner setting limits", async () => {
       try {
-        await program.methods
-          .setLimits(
-            new anchor.BN(1 * 1e8),
-            new anchor.BN(10000 * 1e9),
-            new anchor.BN(60),
-            new anchor.BN(0),
-            new anchor.BN(365 * 86400)
-          )
-          .accounts({ 
-            desk: desk.publicKey,
-            owner: attacker.publicKey,
-          })
-          .signers([attacker])
-          .rpc();
+        await setLimits(
+          new anchor.BN(1 * 1e8),
+          new anchor.BN(10000 * 1e9),
+          new anchor.BN(60),
+          new anchor.BN(0),
+          new anchor.BN(365 * 86400),
+          { signer: attacker }
+        );
         assert.fail("Expected to fail");
       } catch (error) {
         const msg = String(error).toLowerCase();
@@ -317,14 +362,14 @@ describe("OTC Security Audit Tests", () => {
           .accounts({
             tokenRegistry: ownerRegistry,
             desk: desk.publicKey,
-            owner: attacker.publicKey,
+            authority: attacker.publicKey,
           })
           .signers([attacker])
           .rpc();
         assert.fail("Expected to fail");
       } catch (error) {
         const msg = String(error).toLowerCase();
-        assert.isTrue(msg.includes("constraint") || msg.includes("owner"));
+        assert.isTrue(msg.includes("missingrole"));
       }
     });
   });
@@ -337,7 +382,7 @@ describe("OTC Security Audit Tests", () => {
           .unpause()
           .accounts({ 
             desk: desk.publicKey,
-            owner: owner.publicKey,
+            authority: owner.publicKey,
           })
           .signers([owner])
           .rpc();
@@ -347,7 +392,7 @@ describe("OTC Security Audit Tests", () => {
         .pause()
         .accounts({ 
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -359,7 +404,7 @@ describe("OTC Security Audit Tests", () => {
         .unpause()
         .accounts({ 
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -374,18 +419,157 @@ describe("OTC Security Audit Tests", () => {
           .pause()
           .accounts({ 
             desk: desk.publicKey,
-            owner: attacker.publicKey,
+            authority: attacker.publicKey,
           })
           .signers([attacker])
           .rpc();
         assert.fail("Expected to fail");
       } catch (error) {
         const msg = String(error).toLowerCase();
-        assert.isTrue(msg.includes("constraint") || msg.includes("owner"));
+        assert.isTrue(msg.includes("missingrole"));
       }
     });
   });
 
+  describe("Role Grants", () => {
+    let pauser: Keypair;
+    let pauserGrant: PublicKey;
+
+    before(async () => {
+      pauser = Keypair.generate();
+      await airdrop(pauser.publicKey, 1 * LAMPORTS_PER_SOL);
+      pauserGrant = getRoleGrantPda(desk.publicKey, 0, pauser.publicKey); // Role::Pauser
+    });
+
+    it("should let the owner grant a role", async () => {
+      await program.methods
+        .grantRole({ pauser: {} }, pauser.publicKey)
+        .accounts({
+          desk: desk.publicKey,
+          owner: owner.publicKey,
+          roleGrant: pauserGrant,
+        })
+        .signers([owner])
+        .rpc();
+
+      const grant = await program.account.roleGrant.fetch(pauserGrant);
+      assert.equal(grant.holder.toBase58(), pauser.publicKey.toBase58());
+      assert.isDefined(grant.role.pauser);
+    });
+
+    it("should ALLOW a pauser to pause and unpause", async () => {
+      await program.methods
+        .pause()
+        .accounts({ desk: desk.publicKey, authority: pauser.publicKey, role: pauserGrant })
+        .signers([pauser])
+        .rpc();
+      assert.isTrue((await program.account.desk.fetch(desk.publicKey)).paused);
+
+      await program.methods
+        .unpause()
+        .accounts({ desk: desk.publicKey, authority: pauser.publicKey, role: pauserGrant })
+        .signers([pauser])
+        .rpc();
+      assert.isFalse((await program.account.desk.fetch(desk.publicKey)).paused);
+    });
+
+    it("should REJECT a pauser setting prices", async () => {
+      await expectRejectedWith(
+        program.methods
+          .setPrices(new anchor.BN(1 * 1e8), new anchor.BN(100 * 1e8), new anchor.BN(0))
+          .accounts({ desk: desk.publicKey, authority: pauser.publicKey, role: pauserGrant })
+          .signers([pauser])
+          .rpc(),
+        "MissingRole"
+      );
+    });
+
+    it("should REJECT non-owner granting roles", async () => {
+      await expectRejectedWith(
+        program.methods
+          .grantRole({ pauser: {} }, attacker.publicKey)
+          .accounts({
+            desk: desk.publicKey,
+            owner: attacker.publicKey,
+            roleGrant: getRoleGrantPda(desk.publicKey, 0, attacker.publicKey),
+          })
+          .signers([attacker])
+          .rpc(),
+        "NotOwner"
+      );
+    });
+
+    it("should REJECT pausing after the role is revoked", async () => {
+      await program.methods
+        .revokeRole({ pauser: {} }, pauser.publicKey)
+        .accounts({
+          desk: desk.publicKey,
+          owner: owner.publicKey,
+          roleGrant: pauserGrant,
+        })
+        .signers([owner])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(pauserGrant));
+      await expectRejectedWith(
+        program.methods
+          .pause()
+          .accounts({ desk: desk.publicKey, authority: pauser.publicKey, role: null })
+          .signers([pauser])
+          .rpc(),
+        "MissingRole"
+      );
+    });
+
+    it("should ALLOW an approver admin to manage approvers and the approval policy", async () => {
+      const approverAdmin = Keypair.generate();
+      await airdrop(approverAdmin.publicKey, 1 * LAMPORTS_PER_SOL);
+      const adminGrant = getRoleGrantPda(desk.publicKey, 4, approverAdmin.publicKey); // Role::ApproverAdmin
+      await program.methods
+        .grantRole({ approverAdmin: {} }, approverAdmin.publicKey)
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey, roleGrant: adminGrant })
+        .signers([owner])
+        .rpc();
+
+      const newApprover = Keypair.generate().publicKey;
+      await program.methods
+        .setApprover(newApprover, new anchor.BN(1_000 * 1e8), new anchor.BN(10_000 * 1e8), false)
+        .accounts({
+          desk: desk.publicKey,
+          owner: approverAdmin.publicKey,
+          role: adminGrant,
+          approverAccount: getApproverPda(desk.publicKey, newApprover),
+        })
+        .signers([approverAdmin])
+        .rpc();
+      const record = await program.account.approver.fetch(getApproverPda(desk.publicKey, newApprover));
+      assert.equal(record.approver.toBase58(), newApprover.toBase58());
+
+      await program.methods
+        .setSeniorApprovalUsd(new anchor.BN(50_000 * 1e8))
+        .accounts({ desk: desk.publicKey, owner: approverAdmin.publicKey, role: adminGrant })
+        .signers([approverAdmin])
+        .rpc();
+      assert.equal((await program.account.desk.fetch(desk.publicKey)).seniorApprovalUsd8d.toString(), (50_000 * 1e8).toString());
+
+      // The grant does not carry over to other roles
+      await expectRejectedWith(
+        program.methods
+          .pause()
+          .accounts({ desk: desk.publicKey, authority: approverAdmin.publicKey, role: adminGrant })
+          .signers([approverAdmin])
+          .rpc(),
+        "MissingRole"
+      );
+
+      await program.methods
+        .setSeniorApprovalUsd(new anchor.BN(0))
+        .accounts({ desk: desk.publicKey, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    });
+  });
+
   describe("Price Bounds Validation", () => {
     it("should REJECT price = 0", async () => {
       await expectRejectedWith(
@@ -394,7 +578,7 @@ describe("OTC Security Audit Tests", () => {
           .accounts({
             tokenRegistry: ownerRegistry,
             desk: desk.publicKey,
-            owner: owner.publicKey,
+            authority: owner.publicKey,
           })
           .signers([owner])
           .rpc(),
@@ -409,7 +593,7 @@ describe("OTC Security Audit Tests", () => {
           .accounts({
             tokenRegistry: ownerRegistry,
             desk: desk.publicKey,
-            owner: owner.publicKey,
+            authority: owner.publicKey,
           })
           .signers([owner])
           .rpc(),
@@ -424,7 +608,7 @@ describe("OTC Security Audit Tests", () => {
         .accounts({
           tokenRegistry: ownerRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -441,7 +625,7 @@ describe("OTC Security Audit Tests", () => {
         .accounts({
           tokenRegistry: ownerRegistry,
           desk: desk.publicKey,
-          owner: owner.publicKey,
+          authority: owner.publicKey,
         })
         .signers([owner])
         .rpc();
@@ -459,12 +643,11 @@ describe("OTC Security Audit Tests", () => {
           .setPrices(
             new anchor.BN(10 * 1e8),
             new anchor.BN(100000),
-            new anchor.BN(0),
-            new anchor.BN(3600)
+            new anchor.BN(0)
           )
           .accounts({ 
             desk: desk.publicKey,
-            owner: owner.publicKey,
+            authority: owner.publicKey,
           })
           .signers([owner])
           .rpc(),
@@ -478,12 +661,11 @@ describe("OTC Security Audit Tests", () => {
           .setPrices(
             new anchor.BN(10 * 1e8),
             new anchor.BN(100001 * 1e8),
-            new anchor.BN(0),
-            new anchor.BN(3600)
+            new anchor.BN(0)
           )
           .accounts({ 
             desk: desk.publicKey,
-            owner: owner.publicKey,
+            authority: owner.publicKey,
           })
           .signers([owner])
           .rpc(),
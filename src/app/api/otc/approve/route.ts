@@ -541,7 +541,6 @@ async function handleApproval(request: NextRequest) {
           new anchor.BN(tokenPrice8d),
           new anchor.BN(solPrice8d),
           new anchor.BN(now),
-          new anchor.BN(3600), // 1 hour max age
         )
         .accounts({
           owner: ownerKeypair.publicKey,